@@ -3,6 +3,12 @@ use raylib::prelude::*;
 pub struct Assets<'rl> {
     pub destroyed_sounds: Vec<Sound<'rl>>,
     pub bounce_sound: Option<Sound<'rl>>,
+    pub hit_sound: Option<Sound<'rl>>,
+    pub steel_sound: Option<Sound<'rl>>,
+    pub explosive_sound: Option<Sound<'rl>>,
+    pub powerup_sound: Option<Sound<'rl>>,
+    pub laser_sound: Option<Sound<'rl>>,
+    pub life_lost_sound: Option<Sound<'rl>>,
 }
 
 impl<'rl> Assets<'rl> {
@@ -15,10 +21,22 @@ impl<'rl> Assets<'rl> {
             .collect();
 
         let bounce_sound = Sound::load_sound(raudio, "assets/bounce.wav").ok();
+        let hit_sound = Sound::load_sound(raudio, "assets/hit.wav").ok();
+        let steel_sound = Sound::load_sound(raudio, "assets/steel.wav").ok();
+        let explosive_sound = Sound::load_sound(raudio, "assets/explosive.wav").ok();
+        let powerup_sound = Sound::load_sound(raudio, "assets/powerup.wav").ok();
+        let laser_sound = Sound::load_sound(raudio, "assets/laserShoot.wav").ok();
+        let life_lost_sound = Sound::load_sound(raudio, "assets/lifeLost.wav").ok();
 
         Self {
             destroyed_sounds,
             bounce_sound,
+            hit_sound,
+            steel_sound,
+            explosive_sound,
+            powerup_sound,
+            laser_sound,
+            life_lost_sound,
         }
     }
 
@@ -37,4 +55,40 @@ impl<'rl> Assets<'rl> {
             raudio.play_sound(bounce_sound);
         }
     }
+
+    pub fn play_hit(&self, raudio: &RaylibAudio) {
+        if let Some(hit_sound) = &self.hit_sound {
+            raudio.play_sound(hit_sound);
+        }
+    }
+
+    pub fn play_steel(&self, raudio: &RaylibAudio) {
+        if let Some(steel_sound) = &self.steel_sound {
+            raudio.play_sound(steel_sound);
+        }
+    }
+
+    pub fn play_explosive(&self, raudio: &RaylibAudio) {
+        if let Some(explosive_sound) = &self.explosive_sound {
+            raudio.play_sound(explosive_sound);
+        }
+    }
+
+    pub fn play_powerup(&self, raudio: &RaylibAudio) {
+        if let Some(powerup_sound) = &self.powerup_sound {
+            raudio.play_sound(powerup_sound);
+        }
+    }
+
+    pub fn play_laser(&self, raudio: &RaylibAudio) {
+        if let Some(laser_sound) = &self.laser_sound {
+            raudio.play_sound(laser_sound);
+        }
+    }
+
+    pub fn play_life_lost(&self, raudio: &RaylibAudio) {
+        if let Some(life_lost_sound) = &self.life_lost_sound {
+            raudio.play_sound(life_lost_sound);
+        }
+    }
 }
@@ -1,303 +1,260 @@
 use nalgebra::Vector2;
-use raylib::{core::text::measure_text, prelude::*};
+use raylib::prelude::*;
 
-use crate::assets::Assets;
+use self::powerup::{Capsule, Effect, PowerUp};
+
+pub use self::{
+    input::{Action, Control, Input},
+    level::{campaign_checksum, Level, LevelError},
+};
+
+mod collision;
+mod input;
+mod level;
+mod logic;
+mod powerup;
+mod render;
 
 const PLAYER_MAX_LIFE: i32 = 5;
-const LINES_OF_BRICKS: usize = 5;
-const BRICKS_PER_LINE: usize = 20;
+const TICKS_PER_SECOND: u32 = 60;
+/// Countdown before the balls can be served, at the start of every round.
+const READY_TICKS: u32 = 2 * TICKS_PER_SECOND;
+/// Length of the animation played when the last ball drains.
+const LIFE_LOST_TICKS: u32 = 90;
+/// Widest serve angle from the vertical, in degrees.
+const SERVE_ANGLE: f32 = 45.0;
+/// Ticks for the serve aim to sweep from one side to the other and back.
+const SERVE_SWEEP_TICKS: u32 = 2 * TICKS_PER_SECOND;
+/// Cap of the brick combo multiplier.
+const MAX_COMBO: u32 = 8;
 
 #[derive(Default)]
 pub struct Player {
     pub position: Vector2<f32>,
     pub size: Vector2<f32>,
     pub life: i32,
+    /// Horizontal distance moved during the last tick.
+    pub velocity: f32,
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Ball {
     position: Vector2<f32>,
     speed: Vector2<f32>,
     radius: i32,
     active: bool,
+    /// Horizontal offset from the paddle center while the ball is held.
+    stuck_offset: f32,
+}
+
+/// What a brick is made of.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BrickKind {
+    /// Destroyed on the first hit.
+    Normal(Color),
+    /// Takes the given number of hits, its color shows how many are left.
+    Tough(u8),
+    /// Cannot be destroyed, the ball just bounces off.
+    Steel,
+    /// Destroyed on the first hit, along with its neighbors.
+    Explosive,
 }
 
-#[derive(Default)]
 pub struct Brick {
     position: Vector2<f32>,
-    color: Color,
+    kind: BrickKind,
+    /// Hits left before the brick breaks.
+    hits: u8,
+}
+
+impl Brick {
+    fn new(kind: BrickKind, position: Vector2<f32>) -> Self {
+        let hits = match kind {
+            BrickKind::Tough(hits) => hits,
+            _ => 1,
+        };
+
+        Self {
+            position,
+            kind,
+            hits,
+        }
+    }
+
+    /// Points for breaking the brick, steel ones cannot be broken.
+    fn points(&self) -> u32 {
+        match self.kind {
+            BrickKind::Normal(_) => 50,
+            BrickKind::Tough(hits) => 50 * hits as u32,
+            BrickKind::Steel => 0,
+            BrickKind::Explosive => 100,
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self.kind {
+            BrickKind::Normal(color) => color,
+            BrickKind::Tough(_) => match self.hits {
+                1 => Color::SKYBLUE,
+                2 => Color::BLUE,
+                3 => Color::DARKBLUE,
+                4 => Color::VIOLET,
+                _ => Color::DARKPURPLE,
+            },
+            BrickKind::Steel => Color::DARKGRAY,
+            BrickKind::Explosive => Color::ORANGE,
+        }
+    }
+}
+
+/// Something that happened during a tick and that the frontend may want to
+/// react to (e.g. by playing a sound).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    Bounce,
+    Destroyed,
+    /// A tough brick was hit but did not break.
+    Hit,
+    /// The ball bounced off a steel brick.
+    Steel,
+    /// An explosive brick went off.
+    Explosion,
+    /// The paddle caught a capsule.
+    PowerUp,
+    /// The laser paddle fired.
+    Laser,
+    /// The last ball in play drained.
+    LifeLost,
+    /// The game ended, out of lives or with the campaign cleared.
+    GameOver {
+        score: u32,
+    },
 }
 
 pub struct Game {
+    seed: u64,
+    rng: fastrand::Rng,
+    world_size: (f32, f32),
     game_over: bool,
+    /// Whether the run ended by clearing the last level, rather than by
+    /// running out of lives.
+    cleared: bool,
     pause: bool,
+    /// Ticks left before the balls can be served.
+    ready_ticks: u32,
+    /// Ticks left of the life lost animation.
+    lost_ticks: u32,
+    /// Whether the balls on the paddle wait for the serve of the round, rather
+    /// than having been caught by a sticky paddle.
+    serving: bool,
+    /// Ticks spent waiting for the serve, which sweeps the aim.
+    serve_ticks: u32,
+    score: u32,
+    /// Bricks broken since a ball last touched the paddle, multiplies the
+    /// points of the next ones.
+    combo: u32,
     player: Player,
-    ball: Ball,
+    balls: Vec<Ball>,
+    capsules: Vec<Capsule>,
+    effects: Vec<Effect>,
+    lasers: Vec<Vector2<f32>>,
+    laser_cooldown: u32,
     bricks: Vec<Brick>,
     brick_size: Vector2<f32>,
+    levels: Vec<Level>,
+    level: usize,
+    level_ticks: u32,
+    events: Vec<Event>,
+}
+
+impl Game {
+    /// Create a game playing through `levels` in order, with capsule drops
+    /// fully determined by `seed`.
+    pub fn new(levels: Vec<Level>, seed: u64) -> Game {
+        assert!(!levels.is_empty(), "a campaign needs at least one level");
+
+        Game {
+            seed,
+            levels,
+            ..Game::default()
+        }
+    }
+
+    /// Seed of the current run, to be reported along with bug reports.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.game_over
+    }
+
+    fn has_effect(&self, kind: PowerUp) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    /// Direction the balls are served in, in radians from the vertical.
+    fn serve_angle(&self) -> f32 {
+        let widest = SERVE_ANGLE.min(self.levels[self.level].max_bounce_angle);
+        let sweep = self.serve_ticks as f32 / SERVE_SWEEP_TICKS as f32;
+
+        widest.to_radians() * (sweep * std::f32::consts::TAU).sin()
+    }
 }
 
 impl Default for Game {
     fn default() -> Game {
+        let seed = fastrand::u64(..);
+        let rng = fastrand::Rng::with_seed(seed);
+        let world_size = (0.0, 0.0);
         let game_over = false;
+        let cleared = false;
         let pause = false;
+        let ready_ticks = 0;
+        let lost_ticks = 0;
+        let serving = false;
+        let serve_ticks = 0;
+        let score = 0;
+        let combo = 0;
 
         let player = Player::default();
-        let ball = Ball::default();
+        let balls = Vec::new();
+        let capsules = Vec::new();
+        let effects = Vec::new();
+        let lasers = Vec::new();
+        let laser_cooldown = 0;
         let bricks = Vec::new();
         let brick_size = Vector2::default();
+        let levels = vec![Level::classic()];
+        let level = 0;
+        let level_ticks = 0;
+        let events = Vec::new();
 
         Game {
+            seed,
+            rng,
+            world_size,
             game_over,
+            cleared,
             pause,
+            ready_ticks,
+            lost_ticks,
+            serving,
+            serve_ticks,
+            score,
+            combo,
             player,
-            ball,
+            balls,
+            capsules,
+            effects,
+            lasers,
+            laser_cooldown,
             brick_size,
             bricks,
-        }
-    }
-}
-
-impl Game {
-    pub fn init(&mut self, rl: &RaylibHandle) {
-        let (w, h) = (rl.get_screen_width() as f32, rl.get_screen_height() as f32);
-        self.brick_size = Vector2::new(rl.get_screen_width() as f32 / BRICKS_PER_LINE as f32, 40.0);
-
-        // Initialize player
-        self.player.position = Vector2::new(
-            rl.get_screen_width() as f32 / 2.0,
-            rl.get_screen_height() as f32 * 7.0 / 8.0,
-        );
-        self.player.size = Vector2::new(rl.get_screen_width() as f32 / 10.0, 20.0);
-        self.player.life = PLAYER_MAX_LIFE;
-
-        // Initialize ball
-        self.ball.position = Vector2::new(w / 2.0, h * 7.0 / 7.0 - 30.0);
-        self.ball.speed = Vector2::default();
-        self.ball.radius = 7;
-        self.ball.active = false;
-
-        // Initialize bricks
-        let initial_down_position = 50.0;
-
-        self.bricks.clear();
-        for i in 0..LINES_OF_BRICKS {
-            for j in 0..BRICKS_PER_LINE {
-                self.bricks.push(Brick {
-                    position: Vector2::new(
-                        j as f32 * self.brick_size.x + self.brick_size.x / 2.0,
-                        i as f32 * self.brick_size.y + initial_down_position,
-                    ),
-                    color: if (i + j) % 2 == 0 {
-                        Color::GRAY
-                    } else {
-                        Color::LIGHTGRAY
-                    },
-                });
-            }
-        }
-    }
-
-    pub fn update(&mut self, rl: &RaylibHandle, raudio: &RaylibAudio, assets: &Assets) {
-        use raylib::ffi::KeyboardKey::*;
-        let (w, h) = (rl.get_screen_width() as f32, rl.get_screen_height() as f32);
-
-        if !self.game_over {
-            if rl.is_key_pressed(KEY_P) {
-                self.pause = !self.pause;
-            }
-
-            if !self.pause {
-                // player movement logic
-                if rl.is_key_down(KEY_LEFT) {
-                    self.player.position.x -= 5.0;
-                }
-                if self.player.position.x - self.player.size.x / 2.0 <= 0.0 {
-                    self.player.position.x = self.player.size.x / 2.0;
-                }
-                if rl.is_key_down(KEY_RIGHT) {
-                    self.player.position.x += 5.0;
-                }
-                if self.player.position.x + self.player.size.x / 2.0 >= w {
-                    self.player.position.x = w - self.player.size.x / 2.0;
-                }
-
-                // Ball launching logic
-                if !self.ball.active && rl.is_key_pressed(KEY_SPACE) {
-                    self.ball.active = true;
-                    self.ball.speed = Vector2::new(0.0, -5.0);
-                }
-
-                // Ball movement logic
-                if self.ball.active {
-                    self.ball.position += self.ball.speed;
-                } else {
-                    self.ball.position = Vector2::new(self.player.position.x, h * 7.0 / 8.0 - 30.0);
-                }
-
-                // Collision logic: ball vs walls
-                if self.ball.position.x + self.ball.radius as f32 >= w
-                    || self.ball.position.x - self.ball.radius as f32 <= 0.0
-                {
-                    self.ball.speed.x *= -1.0;
-                    assets.play_bounce(raudio);
-                }
-
-                if self.ball.position.y - self.ball.radius as f32 <= 0.0 {
-                    self.ball.speed.y *= -1.0;
-                    assets.play_bounce(raudio);
-                }
-
-                if self.ball.position.y + self.ball.radius as f32 >= h {
-                    self.ball.speed = Vector2::default();
-                    self.ball.active = false;
-                    self.player.life -= 1;
-                }
-
-                // Collision logic: ball vs player
-                let r = Rectangle::new(
-                    self.player.position.x - self.player.size.x / 2.0,
-                    self.player.position.y - self.player.size.y / 2.0,
-                    self.player.size.x,
-                    self.player.size.y,
-                );
-
-                if r.check_collision_circle_rec(self.ball.position, self.ball.radius as f32)
-                    && self.ball.speed.y > 0.0
-                {
-                    self.ball.speed.y *= -1.0;
-                    self.ball.speed.x = (self.ball.position.x - self.player.position.x)
-                        / (self.player.size.x / 2.0)
-                        * 5.0;
-                    assets.play_bounce(raudio);
-                }
-
-                // Collision logic: ball vs bricks
-                self.bricks.retain_mut(|brick| {
-                    // Hit below
-                    if (self.ball.position.y - self.ball.radius as f32
-                        <= brick.position.y + self.brick_size.y / 2.0)
-                        && (self.ball.position.y - self.ball.radius as f32
-                            > brick.position.y + self.brick_size.y / 2.0 + self.ball.speed.y)
-                        && ((self.ball.position.x - brick.position.x).abs()
-                            < self.brick_size.x / 2.0 + self.ball.radius as f32 * 2.0 / 3.0)
-                        && self.ball.speed.y < 0.0
-                    {
-                        self.ball.speed.y *= -1.0;
-                        assets.play_destroyed(raudio);
-                        false
-                    }
-                    // Hit above
-                    else if self.ball.position.y + self.ball.radius as f32
-                        >= brick.position.y - self.brick_size.y / 2.0
-                        && (self.ball.position.y + self.ball.radius as f32)
-                            .partial_cmp(
-                                &(brick.position.y - self.brick_size.y / 2.0 + self.ball.speed.y),
-                            )
-                            .unwrap()
-                            == std::cmp::Ordering::Less
-                        && (self.ball.position.x - brick.position.x).abs()
-                            < self.brick_size.x / 2.0 + self.ball.radius as f32 * 2.0 / 3.0
-                        && self.ball.speed.y > 0.0
-                    {
-                        self.ball.speed.y *= -1.0;
-                        assets.play_bounce(raudio);
-                        false
-                    }
-                    // Hit Left
-                    else if ((self.ball.position.x + self.ball.radius as f32)
-                        >= (brick.position.x - self.brick_size.x / 2.0))
-                        && ((self.ball.position.x + self.ball.radius as f32)
-                            < (brick.position.x - self.brick_size.x / 2.0 + self.ball.speed.x))
-                        && (((self.ball.position.y - brick.position.y).abs())
-                            < (self.brick_size.y / 2.0 + self.ball.radius as f32 * 2.0 / 3.0))
-                        && (self.ball.speed.x > 0.0)
-                    {
-                        self.ball.speed.x *= -1.0;
-                        assets.play_destroyed(raudio);
-                        false
-                    }
-                    // Hit right
-                    else if ((self.ball.position.x - self.ball.radius as f32)
-                        <= (brick.position.x + self.brick_size.x / 2.0))
-                        && ((self.ball.position.x - self.ball.radius as f32)
-                            > (brick.position.x + self.brick_size.x / 2.0 + self.ball.speed.x))
-                        && (((self.ball.position.y - brick.position.y).abs())
-                            < (self.brick_size.y / 2.0 + self.ball.radius as f32 * 2.0 / 3.0))
-                        && (self.ball.speed.x < 0.0)
-                    {
-                        self.ball.speed.x *= -1.0;
-                        assets.play_destroyed(raudio);
-                        false
-                    } else {
-                        true
-                    }
-                });
-
-                // Game over condition
-                if self.player.life <= 0 || self.bricks.is_empty() {
-                    self.game_over = true;
-                }
-            }
-        } else if rl.is_key_pressed(KEY_ENTER) {
-            self.init(rl);
-            self.game_over = false;
-        }
-    }
-
-    pub fn draw(&self, rl: &RaylibHandle, d: &RaylibDrawHandle) {
-        d.draw_fps(10, 10);
-
-        let (w, h) = (rl.get_screen_width() as f32, rl.get_screen_height() as f32);
-
-        d.clear_background(Color::RAYWHITE);
-
-        if !self.game_over {
-            // Draw player bar
-            d.draw_rectangle(
-                (self.player.position.x - self.player.size.x / 2.0) as i32,
-                (self.player.position.y - self.player.size.y / 2.0) as i32,
-                self.player.size.x as i32,
-                self.player.size.y as i32,
-                Color::BLACK,
-            );
-
-            // Draw player lives
-            for i in 0..self.player.life {
-                d.draw_rectangle(20 + 40 * i, h as i32 - 30, 35, 10, Color::LIGHTGRAY);
-            }
-
-            // Draw ball
-            d.draw_circle_v(self.ball.position, self.ball.radius as f32, Color::MAROON);
-
-            // Draw bricks
-            for brick in &self.bricks {
-                d.draw_rectangle(
-                    (brick.position.x - self.brick_size.x / 2.0) as i32,
-                    (brick.position.y - self.brick_size.y / 2.0) as i32,
-                    self.brick_size.x as i32,
-                    self.brick_size.y as i32,
-                    brick.color,
-                );
-            }
-
-            if self.pause {
-                d.draw_text(
-                    "Game Pause",
-                    (w / 2.0) as i32 - measure_text("Game Paused", 40) / 2,
-                    (h / 2.0 - 40.0) as i32,
-                    40,
-                    Color::GRAY,
-                );
-            }
-        } else {
-            d.draw_text(
-                "PRESS [ENTER] TO PLAY AGAIN",
-                (w / 2.0) as i32 - measure_text("PRESS [ENTER] TO PLAY AGAIN", 20) / 2,
-                (h / 2.0) as i32 - 50,
-                20,
-                Color::GRAY,
-            );
+            levels,
+            level,
+            level_ticks,
+            events,
         }
     }
 }
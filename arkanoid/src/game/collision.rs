@@ -0,0 +1,221 @@
+use nalgebra::Vector2;
+
+/// Where a moving circle first touches a box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Contact {
+    /// Part of the motion done when the circle touches the box, from 0 to 1.
+    pub time: f32,
+    /// Normal of the box at the contact point, pointing out of it.
+    pub normal: Vector2<f32>,
+}
+
+/// Earliest contact of a circle of `radius` moving by `motion` from `start`
+/// with the axis-aligned box of `center` and `half_size`, if it happens during
+/// the motion. A circle already touching the box gets a contact at time 0, but
+/// only when moving further into it.
+pub fn sweep_circle_box(
+    start: Vector2<f32>,
+    motion: Vector2<f32>,
+    radius: f32,
+    center: Vector2<f32>,
+    half_size: Vector2<f32>,
+) -> Option<Contact> {
+    let start = start - center;
+
+    let closest = Vector2::new(
+        start.x.clamp(-half_size.x, half_size.x),
+        start.y.clamp(-half_size.y, half_size.y),
+    );
+    let outside = start - closest;
+
+    if outside.norm_squared() <= radius * radius {
+        let normal = if outside.norm_squared() > 0.0 {
+            outside.normalize()
+        } else {
+            // The center is in the box, leave through the nearest side
+            let depth = half_size - start.abs();
+            if depth.x < depth.y {
+                Vector2::new(start.x.signum(), 0.0)
+            } else {
+                Vector2::new(0.0, start.y.signum())
+            }
+        };
+
+        return (motion.dot(&normal) < 0.0).then_some(Contact { time: 0.0, normal });
+    }
+
+    // The circle touches the box when its center enters the box grown by the
+    // radius with rounded corners, i.e. two crossed boxes and four circles.
+    let boxes = [
+        half_size + Vector2::new(radius, 0.0),
+        half_size + Vector2::new(0.0, radius),
+    ];
+    let corners = [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)]
+        .map(|(x, y)| Vector2::new(x * half_size.x, y * half_size.y));
+
+    boxes
+        .iter()
+        .filter_map(|half_size| ray_box(start, motion, *half_size))
+        .chain(
+            corners
+                .iter()
+                .filter_map(|corner| ray_circle(start, motion, *corner, radius)),
+        )
+        .filter(|contact| contact.time <= 1.0)
+        .min_by(|a, b| a.time.total_cmp(&b.time))
+}
+
+/// Where the ray going from `start` along `motion` enters the box of
+/// `half_size` around the origin.
+fn ray_box(start: Vector2<f32>, motion: Vector2<f32>, half_size: Vector2<f32>) -> Option<Contact> {
+    let mut enter = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = Vector2::zeros();
+
+    for axis in 0..2 {
+        if motion[axis] == 0.0 {
+            if start[axis].abs() > half_size[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let direction = motion[axis].signum();
+        let near = (-half_size[axis] * direction - start[axis]) / motion[axis];
+        let far = (half_size[axis] * direction - start[axis]) / motion[axis];
+
+        if near > enter {
+            enter = near;
+            normal = Vector2::zeros();
+            normal[axis] = -direction;
+        }
+        exit = exit.min(far);
+    }
+
+    (enter >= 0.0 && enter <= exit).then_some(Contact {
+        time: enter,
+        normal,
+    })
+}
+
+/// Where the ray going from `start` along `motion` enters the circle of
+/// `center` and `radius`.
+fn ray_circle(
+    start: Vector2<f32>,
+    motion: Vector2<f32>,
+    center: Vector2<f32>,
+    radius: f32,
+) -> Option<Contact> {
+    let offset = start - center;
+
+    let a = motion.norm_squared();
+    let b = 2.0 * offset.dot(&motion);
+    let c = offset.norm_squared() - radius * radius;
+    let discriminant = b * b - 4.0 * a * c;
+
+    if a == 0.0 || discriminant < 0.0 {
+        return None;
+    }
+
+    let time = (-b - discriminant.sqrt()) / (2.0 * a);
+
+    (time >= 0.0).then(|| Contact {
+        time,
+        normal: (offset + motion * time) / radius,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RADIUS: f32 = 5.0;
+
+    fn half_size() -> Vector2<f32> {
+        Vector2::new(20.0, 10.0)
+    }
+
+    fn sweep(start: (f32, f32), motion: (f32, f32)) -> Option<Contact> {
+        sweep_circle_box(
+            Vector2::new(start.0, start.1),
+            Vector2::new(motion.0, motion.1),
+            RADIUS,
+            Vector2::zeros(),
+            half_size(),
+        )
+    }
+
+    fn assert_contact(contact: Option<Contact>, time: f32, normal: (f32, f32)) {
+        let contact = contact.expect("no contact");
+
+        assert!((contact.time - time).abs() < 1e-4, "time {}", contact.time);
+        assert!(
+            (contact.normal - Vector2::new(normal.0, normal.1)).norm() < 1e-4,
+            "normal {:?}",
+            contact.normal
+        );
+    }
+
+    #[test]
+    fn hits_each_face() {
+        assert_contact(sweep((-40.0, 0.0), (30.0, 0.0)), 0.5, (-1.0, 0.0));
+        assert_contact(sweep((40.0, 0.0), (-30.0, 0.0)), 0.5, (1.0, 0.0));
+        assert_contact(sweep((0.0, -30.0), (0.0, 20.0)), 0.75, (0.0, -1.0));
+        assert_contact(sweep((5.0, 30.0), (0.0, -20.0)), 0.75, (0.0, 1.0));
+    }
+
+    #[test]
+    fn hits_rounded_corner() {
+        // Heading straight for the top left corner, the circle touches it
+        // RADIUS away from it, later than the corner of the grown box
+        let corner = Vector2::new(-20.0, -10.0);
+        let start = corner + Vector2::new(-20.0, -20.0);
+        let motion = Vector2::new(30.0, 30.0);
+        let time = (20.0 * 2f32.sqrt() - RADIUS) / motion.norm();
+
+        let diagonal = -1.0 / 2f32.sqrt();
+        assert_contact(
+            sweep((start.x, start.y), (motion.x, motion.y)),
+            time,
+            (diagonal, diagonal),
+        );
+    }
+
+    #[test]
+    fn misses_past_rounded_corner() {
+        // Cuts through the corner of the box grown by the radius, but stays
+        // more than RADIUS away from the corner of the box itself
+        assert!(sweep((-4.0, -34.0), (-40.0, 40.0)).is_none());
+        assert!(sweep((-4.0, -33.0), (-40.0, 40.0)).is_some());
+    }
+
+    #[test]
+    fn touching_at_start() {
+        assert_contact(sweep((-25.0, 0.0), (10.0, 0.0)), 0.0, (-1.0, 0.0));
+        // Overlapping, the ball leaves through the nearest side
+        assert_contact(sweep((0.0, 8.0), (0.0, -5.0)), 0.0, (0.0, 1.0));
+    }
+
+    #[test]
+    fn moving_away_does_not_hit() {
+        assert!(sweep((-25.0, 0.0), (-10.0, 0.0)).is_none());
+        assert!(sweep((0.0, 8.0), (0.0, 5.0)).is_none());
+        assert!(sweep((-40.0, 0.0), (-30.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn too_short_does_not_hit() {
+        assert!(sweep((-40.0, 0.0), (14.0, 0.0)).is_none());
+        assert_contact(sweep((-40.0, 0.0), (15.0, 0.0)), 1.0, (-1.0, 0.0));
+    }
+
+    #[test]
+    fn fast_ball_does_not_tunnel() {
+        // Both ends of the motion are clear of the box
+        let (start, motion) = ((-100.0, 0.0), (200.0, 0.0));
+        let end = Vector2::new(start.0 + motion.0, start.1 + motion.1);
+        assert!(end.x - RADIUS > half_size().x);
+
+        assert_contact(sweep(start, motion), 75.0 / 200.0, (-1.0, 0.0));
+    }
+}
@@ -0,0 +1,179 @@
+use common::input::{self, Actions, Binding};
+use raylib::{
+    ffi::{GamepadAxis, GamepadButton, KeyboardKey, MouseButton},
+    prelude::*,
+};
+
+/// What the player can do, as named in bindings files.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    Left,
+    Right,
+    Launch,
+    Pause,
+    Restart,
+}
+
+impl input::Action for Action {
+    const ALL: &'static [Self] = &[
+        Action::Left,
+        Action::Right,
+        Action::Launch,
+        Action::Pause,
+        Action::Restart,
+    ];
+    const SETTINGS: &'static [&'static str] = &["control"];
+
+    fn name(self) -> &'static str {
+        match self {
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Launch => "launch",
+            Action::Pause => "pause",
+            Action::Restart => "restart",
+        }
+    }
+
+    fn default_bindings(self) -> &'static [Binding] {
+        use Binding::*;
+
+        match self {
+            Action::Left => &[
+                Key(KeyboardKey::KEY_LEFT),
+                Button(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT),
+                Axis {
+                    axis: GamepadAxis::GAMEPAD_AXIS_LEFT_X,
+                    positive: false,
+                },
+            ],
+            Action::Right => &[
+                Key(KeyboardKey::KEY_RIGHT),
+                Button(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT),
+                Axis {
+                    axis: GamepadAxis::GAMEPAD_AXIS_LEFT_X,
+                    positive: true,
+                },
+            ],
+            Action::Launch => &[
+                Key(KeyboardKey::KEY_SPACE),
+                Button(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN),
+            ],
+            // Pausing and restarting never happen at the same time
+            Action::Pause => &[
+                Key(KeyboardKey::KEY_P),
+                Button(GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT),
+            ],
+            Action::Restart => &[
+                Key(KeyboardKey::KEY_ENTER),
+                Button(GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT),
+            ],
+        }
+    }
+}
+
+/// How the paddle is steered, on top of the bound actions.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Control {
+    #[default]
+    Keyboard,
+    /// The paddle follows the mouse cursor.
+    Mouse,
+    /// The paddle moves along with the mouse, whose cursor is captured.
+    Relative,
+    /// The paddle speed follows how far the bindings are pushed, e.g. the
+    /// deflection of a stick.
+    Analog,
+}
+
+impl Control {
+    /// Parse the name of a control, as given to `--control` or as the
+    /// `control` setting of the bindings file.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "keyboard" => Some(Control::Keyboard),
+            "mouse" => Some(Control::Mouse),
+            "relative" => Some(Control::Relative),
+            "analog" => Some(Control::Analog),
+            _ => None,
+        }
+    }
+}
+
+/// Player inputs for a single game tick.
+///
+/// The game only ever reads inputs through this struct, so it can be stepped
+/// without a window or audio device by building frames by hand.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct Input {
+    pub left: bool,
+    pub right: bool,
+    pub launch: bool,
+    pub pause: bool,
+    pub restart: bool,
+    /// Where the paddle heads to, in pixels, when it follows the mouse.
+    pub target: Option<i16>,
+    /// Distance the mouse pushes the paddle by, in pixels.
+    pub motion: i16,
+    /// Stick position, from -127 (full left) to 127 (full right).
+    pub axis: i8,
+}
+
+impl Input {
+    /// Read the actions of the current frame, along with the mouse for the
+    /// controls using it.
+    pub fn poll(rl: &RaylibHandle, actions: &Actions<Action>, control: Control) -> Self {
+        let mut input = Self {
+            left: actions.is_down(Action::Left),
+            right: actions.is_down(Action::Right),
+            launch: actions.is_pressed(Action::Launch),
+            pause: actions.is_pressed(Action::Pause),
+            restart: actions.is_pressed(Action::Restart),
+            ..Self::default()
+        };
+
+        match control {
+            Control::Keyboard => {}
+            Control::Mouse => {
+                input.target = Some(rl.get_mouse_x().clamp(0, i16::MAX.into()) as i16);
+                input.launch |= rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
+            }
+            Control::Relative => {
+                input.motion = rl.get_mouse_delta().x.round() as i16;
+                input.launch |= rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
+            }
+            // Keys and buttons count as a stick pushed all the way
+            Control::Analog => {
+                let axis = actions.value(Action::Right) - actions.value(Action::Left);
+
+                input.axis = (axis * i8::MAX as f32) as i8;
+                input.left = false;
+                input.right = false;
+            }
+        }
+
+        input
+    }
+
+    /// Pack the key inputs in a bit set, one bit per field in declaration
+    /// order. Analog ones are left out.
+    pub fn to_bits(self) -> u8 {
+        [self.left, self.right, self.launch, self.pause, self.restart]
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (i, &pressed)| bits | (pressed as u8) << i)
+    }
+
+    /// Inverse of [`Input::to_bits`], analog inputs are left at rest.
+    pub fn from_bits(bits: u8) -> Self {
+        let bit = |i: u8| bits & (1 << i) != 0;
+
+        Self {
+            left: bit(0),
+            right: bit(1),
+            launch: bit(2),
+            pause: bit(3),
+            restart: bit(4),
+            ..Self::default()
+        }
+    }
+}
@@ -0,0 +1,487 @@
+//! Text based level files.
+//!
+//! A level file starts with `key: value` metadata lines, followed by a `---`
+//! separator and the brick grid, one character per brick:
+//!
+//! ```text
+//! # Lines starting with '#' before the separator are comments.
+//! name: Classic
+//! ball_speed: 5
+//! par_time: 90
+//! ---
+//! glglglglglglglglglgl
+//! lglglglglglglglglglg
+//! ```
+//!
+//! `name` is required, `ball_speed` (pixels per tick), `par_time` (seconds),
+//! `brick_height` (pixels), `balls` (balls served each round, up to
+//! [`MAX_SERVED_BALLS`]) and `ball_collisions` (`true` or `false`) are optional.
+//!
+//! How the ball leaves the paddle can be tuned too: `max_angle` (degrees from
+//! the vertical at the paddle edges, up to [`MAX_BOUNCE_ANGLE`]), `speed_ramp`
+//! (speed gained on every paddle hit), `max_ball_speed` (pixels per tick) and
+//! `english` (share of the paddle speed passed on to the ball).
+//!
+//! In the grid, `.` is an empty slot, letters are plain bricks of the color
+//! given by [`brick_color`], digits `2` to `5` are tough bricks taking that
+//! many hits, `#` is a steel brick and `*` an explosive one.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use raylib::prelude::*;
+
+use super::BrickKind;
+
+const DEFAULT_BALL_SPEED: f32 = 5.0;
+const DEFAULT_BRICK_HEIGHT: f32 = 40.0;
+const DEFAULT_BOUNCE_ANGLE: f32 = 60.0;
+const DEFAULT_SPEED_RAMP: f32 = 0.1;
+/// Top ball speed relative to `ball_speed`, when not given.
+const DEFAULT_MAX_SPEED_FACTOR: f32 = 1.6;
+const DEFAULT_ENGLISH: f32 = 0.3;
+/// Most balls a level can serve at once, they all have to fit on the paddle.
+pub const MAX_SERVED_BALLS: usize = 5;
+/// Steepest angle from the vertical a ball may leave the paddle at, so that it
+/// never goes flat.
+pub const MAX_BOUNCE_ANGLE: f32 = 80.0;
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+pub struct Level {
+    pub name: String,
+    pub ball_speed: f32,
+    /// Time to beat to clear the level, in seconds.
+    pub par_time: Option<u32>,
+    pub brick_height: f32,
+    /// Balls put on the paddle at the start of each round.
+    pub balls: usize,
+    /// Whether balls bounce off each other.
+    pub ball_collisions: bool,
+    /// Angle from the vertical balls leave the paddle edges at, in degrees.
+    pub max_bounce_angle: f32,
+    /// Speed gained by a ball every time it hits the paddle.
+    pub speed_ramp: f32,
+    /// Fastest the balls get, in pixels per tick.
+    pub max_ball_speed: f32,
+    /// Share of the paddle speed passed on to the balls it hits.
+    pub english: f32,
+    /// Brick rows from top to bottom, `None` for empty slots.
+    pub grid: Vec<Vec<Option<BrickKind>>>,
+    /// Hash of the level file, telling apart edited levels.
+    pub checksum: u64,
+}
+
+/// Error found while parsing a level, `line` is 1-based.
+#[derive(Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, ParseError),
+    NoLevels(PathBuf),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(path, err) => write!(f, "{}: {err}", path.display()),
+            LevelError::Parse(path, err) => write!(f, "{}: {err}", path.display()),
+            LevelError::NoLevels(path) => write!(f, "{}: no level files found", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+impl std::error::Error for LevelError {}
+
+/// Color of the plain brick described by `c` in a level grid.
+pub fn brick_color(c: char) -> Option<Color> {
+    Some(match c {
+        'g' => Color::GRAY,
+        'l' => Color::LIGHTGRAY,
+        'd' => Color::DARKGRAY,
+        'r' => Color::RED,
+        'o' => Color::ORANGE,
+        'y' => Color::GOLD,
+        'e' => Color::GREEN,
+        'b' => Color::BLUE,
+        's' => Color::SKYBLUE,
+        'p' => Color::PURPLE,
+        'k' => Color::PINK,
+        'w' => Color::BEIGE,
+        _ => return None,
+    })
+}
+
+impl Level {
+    /// The original hard-coded wall, used when no level files are available.
+    pub fn classic() -> Self {
+        Self {
+            name: "Classic".to_string(),
+            ball_speed: DEFAULT_BALL_SPEED,
+            par_time: None,
+            brick_height: DEFAULT_BRICK_HEIGHT,
+            balls: 1,
+            ball_collisions: false,
+            max_bounce_angle: DEFAULT_BOUNCE_ANGLE,
+            speed_ramp: DEFAULT_SPEED_RAMP,
+            max_ball_speed: DEFAULT_BALL_SPEED * DEFAULT_MAX_SPEED_FACTOR,
+            english: DEFAULT_ENGLISH,
+            grid: (0..5)
+                .map(|i| {
+                    (0..20)
+                        .map(|j| {
+                            Some(BrickKind::Normal(if (i + j) % 2 == 0 {
+                                Color::GRAY
+                            } else {
+                                Color::LIGHTGRAY
+                            }))
+                        })
+                        .collect()
+                })
+                .collect(),
+            checksum: fnv1a(FNV_OFFSET, b"classic"),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let error = |line: usize, message: String| ParseError {
+            line: line + 1,
+            message,
+        };
+
+        let mut lines = text.lines().enumerate();
+        let last_line = text.lines().count().saturating_sub(1);
+
+        let mut name = None;
+        let mut ball_speed = DEFAULT_BALL_SPEED;
+        let mut par_time = None;
+        let mut brick_height = DEFAULT_BRICK_HEIGHT;
+        let mut balls = 1;
+        let mut ball_collisions = false;
+        let mut max_bounce_angle = DEFAULT_BOUNCE_ANGLE;
+        let mut speed_ramp = DEFAULT_SPEED_RAMP;
+        let mut max_ball_speed = None;
+        let mut english = DEFAULT_ENGLISH;
+
+        // Metadata
+        loop {
+            let Some((n, line)) = lines.next() else {
+                return Err(error(last_line, "missing '---' separator".into()));
+            };
+            let line = line.trim();
+
+            if line == "---" {
+                break;
+            }
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once(':') else {
+                return Err(error(n, format!("expected 'key: value', found '{line}'")));
+            };
+            let (key, value) = (key.trim(), value.trim());
+
+            let number = |value: &str| {
+                value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|v| v.is_finite() && *v > 0.0)
+                    .ok_or_else(|| {
+                        error(
+                            n,
+                            format!("{key} must be a positive number, found '{value}'"),
+                        )
+                    })
+            };
+            let non_negative = |value: &str| {
+                value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|v| v.is_finite() && *v >= 0.0)
+                    .ok_or_else(|| {
+                        error(
+                            n,
+                            format!("{key} must be zero or a positive number, found '{value}'"),
+                        )
+                    })
+            };
+
+            match key {
+                "name" => name = Some(value.to_string()),
+                "ball_speed" => ball_speed = number(value)?,
+                "brick_height" => brick_height = number(value)?,
+                "max_angle" => {
+                    max_bounce_angle = number(value)?;
+                    if max_bounce_angle > MAX_BOUNCE_ANGLE {
+                        return Err(error(
+                            n,
+                            format!(
+                                "max_angle must be at most {MAX_BOUNCE_ANGLE}, found '{value}'"
+                            ),
+                        ));
+                    }
+                }
+                "speed_ramp" => speed_ramp = non_negative(value)?,
+                "max_ball_speed" => max_ball_speed = Some((n, number(value)?)),
+                "english" => english = non_negative(value)?,
+                "par_time" => {
+                    par_time = Some(value.parse().map_err(|_| {
+                        error(
+                            n,
+                            format!("par_time must be a number of seconds, found '{value}'"),
+                        )
+                    })?)
+                }
+                "balls" => {
+                    balls = value
+                        .parse()
+                        .ok()
+                        .filter(|balls| (1..=MAX_SERVED_BALLS).contains(balls))
+                        .ok_or_else(|| {
+                            error(
+                                n,
+                                format!(
+                                "balls must be between 1 and {MAX_SERVED_BALLS}, found '{value}'"
+                            ),
+                            )
+                        })?
+                }
+                "ball_collisions" => {
+                    ball_collisions = value.parse().map_err(|_| {
+                        error(
+                            n,
+                            format!("ball_collisions must be true or false, found '{value}'"),
+                        )
+                    })?
+                }
+                _ => return Err(error(n, format!("unknown key '{key}'"))),
+            }
+        }
+
+        let name = name.ok_or_else(|| error(0, "missing 'name'".into()))?;
+
+        let max_ball_speed = match max_ball_speed {
+            Some((n, max_ball_speed)) if max_ball_speed < ball_speed => {
+                return Err(error(
+                    n,
+                    format!("max_ball_speed cannot be below ball_speed ({ball_speed})"),
+                ))
+            }
+            Some((_, max_ball_speed)) => max_ball_speed,
+            None => ball_speed * DEFAULT_MAX_SPEED_FACTOR,
+        };
+
+        // Bricks
+        let mut grid: Vec<Vec<Option<BrickKind>>> = Vec::new();
+        for (n, line) in lines {
+            let line = line.trim_end();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(column, c)| match c {
+                    '.' => Ok(None),
+                    '#' => Ok(Some(BrickKind::Steel)),
+                    '*' => Ok(Some(BrickKind::Explosive)),
+                    '2'..='5' => Ok(Some(BrickKind::Tough(c as u8 - b'0'))),
+                    c => brick_color(c)
+                        .map(|color| Some(BrickKind::Normal(color)))
+                        .ok_or_else(|| {
+                            error(n, format!("unknown brick '{c}' at column {}", column + 1))
+                        }),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if let Some(first) = grid.first() {
+                if row.len() != first.len() {
+                    return Err(error(
+                        n,
+                        format!("row has {} bricks, expected {}", row.len(), first.len()),
+                    ));
+                }
+            }
+
+            grid.push(row);
+        }
+
+        if !grid
+            .iter()
+            .flatten()
+            .any(|kind| kind.is_some_and(|kind| kind != BrickKind::Steel))
+        {
+            return Err(error(last_line, "level has no breakable bricks".into()));
+        }
+
+        Ok(Self {
+            name,
+            ball_speed,
+            par_time,
+            brick_height,
+            balls,
+            ball_collisions,
+            max_bounce_angle,
+            speed_ramp,
+            max_ball_speed,
+            english,
+            grid,
+            checksum: fnv1a(FNV_OFFSET, text.as_bytes()),
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LevelError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|err| LevelError::Io(path.into(), err))?;
+
+        Self::parse(&text).map_err(|err| LevelError::Parse(path.into(), err))
+    }
+
+    /// Load every `*.txt` level of `dir`, ordered by file name.
+    pub fn load_campaign(dir: impl AsRef<Path>) -> Result<Vec<Self>, LevelError> {
+        let dir = dir.as_ref();
+        let io_error = |err| LevelError::Io(dir.into(), err);
+
+        let mut paths = fs::read_dir(dir)
+            .map_err(io_error)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io_error)?;
+
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "txt"));
+        paths.sort();
+
+        if paths.is_empty() {
+            return Err(LevelError::NoLevels(dir.into()));
+        }
+
+        paths.iter().map(Self::load).collect()
+    }
+
+    pub fn columns(&self) -> usize {
+        self.grid[0].len()
+    }
+}
+
+/// Hash of every level of a campaign, in order. Replays only play back against
+/// the campaign they were recorded with.
+pub fn campaign_checksum(levels: &[Level]) -> u64 {
+    levels.iter().fold(FNV_OFFSET, |hash, level| {
+        fnv1a(hash, &level.checksum.to_le_bytes())
+    })
+}
+
+/// FNV-1a hash of `bytes` continuing from `hash`, which unlike the hasher of
+/// the standard library stays the same across builds.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(text: &str) -> ParseError {
+        match Level::parse(text) {
+            Ok(level) => panic!("level '{}' parsed", level.name),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn parses_metadata_and_grid() {
+        let level = Level::parse("name: Test\nballs: 2\n---\nr.#\n23*\n").unwrap();
+
+        assert_eq!(level.name, "Test");
+        assert_eq!(level.balls, 2);
+        assert_eq!(level.columns(), 3);
+        assert_eq!(
+            level.grid[1],
+            [
+                Some(BrickKind::Tough(2)),
+                Some(BrickKind::Tough(3)),
+                Some(BrickKind::Explosive)
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_name() {
+        let err = parse_error("ball_speed: 5\n---\nrrr\n");
+
+        assert_eq!(err.line, 1);
+        assert_eq!(err.message, "missing 'name'");
+    }
+
+    #[test]
+    fn bad_balls() {
+        for balls in ["0", "6", "two"] {
+            let err = parse_error(&format!("name: Test\nballs: {balls}\n---\nrrr\n"));
+
+            assert_eq!(err.line, 2);
+            assert_eq!(
+                err.message,
+                format!("balls must be between 1 and {MAX_SERVED_BALLS}, found '{balls}'")
+            );
+        }
+    }
+
+    #[test]
+    fn ragged_rows() {
+        let err = parse_error("name: Test\n---\nrrr\nrr\n");
+
+        assert_eq!(err.line, 4);
+        assert_eq!(err.message, "row has 2 bricks, expected 3");
+    }
+
+    #[test]
+    fn unknown_brick() {
+        let err = parse_error("name: Test\n---\nrrr\nr?r\n");
+
+        assert_eq!(err.line, 4);
+        assert_eq!(err.message, "unknown brick '?' at column 2");
+    }
+
+    #[test]
+    fn empty_grid() {
+        for text in ["name: Test\n---\n", "name: Test\n---\n...\n###\n"] {
+            let err = parse_error(text);
+
+            assert_eq!(err.message, "level has no breakable bricks");
+        }
+    }
+
+    #[test]
+    fn checksum_follows_the_text() {
+        let level = |text| Level::parse(text).unwrap();
+        let first = level("name: Test\n---\nrrr\n");
+        let edited = level("name: Test\nball_speed: 6\n---\nrrr\n");
+
+        assert_eq!(first.checksum, level("name: Test\n---\nrrr\n").checksum);
+        assert_ne!(first.checksum, edited.checksum);
+        assert_ne!(
+            campaign_checksum(&[first]),
+            campaign_checksum(&[edited, Level::classic()])
+        );
+    }
+}
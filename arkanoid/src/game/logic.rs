@@ -0,0 +1,849 @@
+use nalgebra::{Rotation2, Vector2};
+use raylib::prelude::*;
+
+use super::{collision, powerup::*, *};
+
+const PADDLE_SPEED: f32 = 5.0;
+/// Paddle speed with the stick pushed all the way.
+const ANALOG_SPEED: f32 = 9.0;
+/// Top speed of the paddle following the mouse.
+const FOLLOW_SPEED: f32 = 14.0;
+/// Speed the paddle following the mouse gains or loses in a tick.
+const FOLLOW_ACCELERATION: f32 = 1.5;
+const EXPAND_FACTOR: f32 = 1.5;
+const SLOW_FACTOR: f32 = 0.6;
+const MAX_BALLS: usize = 16;
+const BALL_RADIUS: i32 = 7;
+const LASER_SPEED: f32 = 8.0;
+const LASER_COOLDOWN: u32 = 15;
+const CAPSULE_POINTS: u32 = 100;
+const LEVEL_POINTS: u32 = 1000;
+/// Bonus for each second under the par time of a level.
+const PAR_SECOND_POINTS: u32 = 50;
+/// Bricks a ball may bounce off during a single step.
+const MAX_BOUNCES: usize = 4;
+
+/// Bricks broken during a tick. Explosions are only resolved once everything
+/// moved, so that a chain reaction happens in one go.
+#[derive(Default)]
+struct Breakage {
+    explosions: Vec<Vector2<f32>>,
+    /// Position and points of each destroyed brick.
+    destroyed: Vec<(Vector2<f32>, u32)>,
+}
+
+impl Game {
+    /// Reset the game for a world of `world_size` (width, height).
+    pub fn init(&mut self, world_size: (f32, f32)) {
+        self.world_size = world_size;
+        self.rng.seed(self.seed);
+        self.player.life = PLAYER_MAX_LIFE;
+        self.level = 0;
+        self.score = 0;
+        self.cleared = false;
+
+        self.load_level();
+    }
+
+    /// Set up the playfield for the current level.
+    fn load_level(&mut self) {
+        let (w, h) = self.world_size;
+        let level = &self.levels[self.level];
+
+        self.level_ticks = 0;
+        self.brick_size = Vector2::new(w / level.columns() as f32, level.brick_height);
+
+        // Initialize player
+        self.player.position = Vector2::new(w / 2.0, h * 7.0 / 8.0);
+        self.player.size = Vector2::new(w / 10.0, 20.0);
+
+        // Initialize bricks
+        let initial_down_position = 50.0;
+
+        self.bricks.clear();
+        for (i, row) in level.grid.iter().enumerate() {
+            for (j, kind) in row.iter().enumerate() {
+                if let Some(kind) = *kind {
+                    self.bricks.push(Brick::new(
+                        kind,
+                        Vector2::new(
+                            j as f32 * self.brick_size.x + self.brick_size.x / 2.0,
+                            i as f32 * self.brick_size.y + initial_down_position,
+                        ),
+                    ));
+                }
+            }
+        }
+
+        self.reset_round();
+    }
+
+    /// Put the paddle back in the center with the level's balls on it, drop
+    /// every power-up in play and start the countdown to the serve.
+    fn reset_round(&mut self) {
+        let w = self.world_size.0;
+        let count = self.levels[self.level].balls;
+        let radius = BALL_RADIUS;
+
+        self.player.position.x = w / 2.0;
+        self.player.velocity = 0.0;
+        let paddle_top = self.player.position.y - self.player.size.y / 2.0;
+
+        // Initialize balls, side by side on the paddle
+        self.balls.clear();
+        for i in 0..count {
+            let stuck_offset = (i as f32 - (count - 1) as f32 / 2.0) * (radius * 2 + 2) as f32;
+
+            self.balls.push(Ball {
+                position: Vector2::new(w / 2.0 + stuck_offset, paddle_top - radius as f32),
+                speed: Vector2::default(),
+                radius,
+                active: false,
+                stuck_offset,
+            });
+        }
+
+        self.capsules.clear();
+        self.effects.clear();
+        self.lasers.clear();
+        self.laser_cooldown = 0;
+        self.combo = 0;
+
+        self.ready_ticks = READY_TICKS;
+        self.serving = true;
+        self.serve_ticks = 0;
+    }
+
+    /// Advance the game by one tick.
+    pub fn update(&mut self, input: &Input) {
+        if !self.game_over {
+            if input.pause {
+                self.pause = !self.pause;
+            }
+
+            if !self.pause {
+                if self.lost_ticks > 0 {
+                    self.lost_ticks -= 1;
+
+                    // Game over condition, once the animation played
+                    if self.lost_ticks == 0 {
+                        if self.player.life <= 0 {
+                            self.game_over = true;
+                        } else {
+                            self.reset_round();
+                        }
+                    }
+                } else if self.ready_ticks > 0 {
+                    self.ready_ticks -= 1;
+                } else {
+                    self.play(input);
+                }
+
+                if self.game_over {
+                    self.events.push(Event::GameOver { score: self.score });
+                }
+            }
+        } else if input.restart {
+            // Every run gets its own seed so that it can be replayed on its own.
+            self.seed = self.rng.u64(..);
+            self.init(self.world_size);
+            self.game_over = false;
+        }
+    }
+
+    /// Move everything in play by one tick.
+    fn play(&mut self, input: &Input) {
+        self.level_ticks += 1;
+        if self.serving {
+            self.serve_ticks += 1;
+        }
+
+        let mut breakage = Breakage::default();
+
+        self.update_effects();
+        self.move_player(input);
+        self.launch(input);
+        self.update_balls(&mut breakage);
+        self.update_lasers(&mut breakage);
+        self.detonate(&mut breakage);
+        self.score_bricks(&breakage.destroyed);
+        self.drop_capsules(&breakage.destroyed);
+        self.update_capsules();
+
+        if self
+            .bricks
+            .iter()
+            .all(|brick| brick.kind == BrickKind::Steel)
+        {
+            self.score += self.level_bonus();
+
+            // Move on to the next level, the campaign ends after the last one.
+            if self.level + 1 < self.levels.len() {
+                self.level += 1;
+                self.load_level();
+            } else {
+                self.game_over = true;
+                self.cleared = true;
+            }
+        } else if self.balls.is_empty() {
+            // A life is only lost once the last ball drained, unless it took
+            // the last brick with it.
+            self.player.life -= 1;
+            self.lost_ticks = LIFE_LOST_TICKS;
+            self.events.push(Event::LifeLost);
+        }
+    }
+
+    /// Take the events produced since the last call.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Serve and top speeds of the balls, in pixels per tick.
+    fn ball_speed(&self) -> (f32, f32) {
+        let level = &self.levels[self.level];
+        let factor = if self.has_effect(PowerUp::Slow) {
+            SLOW_FACTOR
+        } else {
+            1.0
+        };
+
+        (level.ball_speed * factor, level.max_ball_speed * factor)
+    }
+
+    /// Every brick broken without the balls touching the paddle raises the
+    /// multiplier of the next ones.
+    fn score_bricks(&mut self, destroyed: &[(Vector2<f32>, u32)]) {
+        for &(_, points) in destroyed {
+            self.combo += 1;
+            self.score += points * self.combo.min(MAX_COMBO);
+        }
+    }
+
+    /// Points for clearing the current level, more when beating its par time.
+    fn level_bonus(&self) -> u32 {
+        let seconds = self.level_ticks / TICKS_PER_SECOND;
+        let under_par = self.levels[self.level]
+            .par_time
+            .map_or(0, |par_time| par_time.saturating_sub(seconds));
+
+        LEVEL_POINTS + under_par * PAR_SECOND_POINTS
+    }
+
+    fn paddle(&self) -> Rectangle {
+        Rectangle::new(
+            self.player.position.x - self.player.size.x / 2.0,
+            self.player.position.y - self.player.size.y / 2.0,
+            self.player.size.x,
+            self.player.size.y,
+        )
+    }
+
+    fn update_effects(&mut self) {
+        for effect in &mut self.effects {
+            effect.ticks_left -= 1;
+
+            if effect.ticks_left == 0 && effect.kind == PowerUp::Slow {
+                for ball in &mut self.balls {
+                    ball.speed /= SLOW_FACTOR;
+                }
+            }
+        }
+
+        self.effects.retain(|effect| effect.ticks_left > 0);
+
+        let width = self.world_size.0 / 10.0;
+        self.player.size.x = if self.has_effect(PowerUp::Expand) {
+            width * EXPAND_FACTOR
+        } else {
+            width
+        };
+    }
+
+    fn move_player(&mut self, input: &Input) {
+        let w = self.world_size.0;
+        let previous_x = self.player.position.x;
+
+        if input.left {
+            self.player.position.x -= PADDLE_SPEED;
+        }
+        if input.right {
+            self.player.position.x += PADDLE_SPEED;
+        }
+
+        self.player.position.x += input.axis as f32 / i8::MAX as f32 * ANALOG_SPEED;
+        self.player.position.x += input.motion as f32;
+
+        // Head for the mouse as fast as possible, yet smoothly: speed changes
+        // are limited and the paddle slows down in time to stop on target.
+        if let Some(target) = input.target {
+            let distance = target as f32 - previous_x;
+            // Braking from `v` one step at a time covers v + (v - a) + ... ~
+            // v * v / 2a + v / 2, which must stay within the distance left.
+            let braking = FOLLOW_ACCELERATION
+                * ((0.25 + 2.0 * distance.abs() / FOLLOW_ACCELERATION).sqrt() - 0.5);
+            let wanted = distance.signum() * distance.abs().min(FOLLOW_SPEED).min(braking);
+            let change =
+                (wanted - self.player.velocity).clamp(-FOLLOW_ACCELERATION, FOLLOW_ACCELERATION);
+
+            self.player.position.x += self.player.velocity + change;
+        }
+
+        let half_width = self.player.size.x / 2.0;
+        self.player.position.x = self.player.position.x.clamp(half_width, w - half_width);
+
+        self.player.velocity = self.player.position.x - previous_x;
+    }
+
+    /// Release the balls held by the paddle, or fire the lasers.
+    fn launch(&mut self, input: &Input) {
+        if !input.launch {
+            return;
+        }
+
+        let (speed, _) = self.ball_speed();
+
+        // The round starts with every ball served at the aimed angle
+        if self.serving {
+            let angle = self.serve_angle();
+
+            for ball in &mut self.balls {
+                ball.active = true;
+                ball.speed = Vector2::new(angle.sin(), -angle.cos()) * speed;
+            }
+            self.serving = false;
+            return;
+        }
+
+        let half_width = self.player.size.x / 2.0;
+        let level = &self.levels[self.level];
+        let mut launched = false;
+
+        for ball in self.balls.iter_mut().filter(|ball| !ball.active) {
+            ball.active = true;
+            ball.speed = paddle_bounce(
+                ball.stuck_offset / half_width,
+                self.player.velocity,
+                speed,
+                level,
+            );
+            launched = true;
+        }
+
+        if !launched && self.has_effect(PowerUp::Laser) && self.laser_cooldown == 0 {
+            let y = self.player.position.y - self.player.size.y / 2.0;
+
+            self.lasers.extend(
+                [-1.0, 1.0].map(|side| {
+                    Vector2::new(self.player.position.x + side * (half_width - 5.0), y)
+                }),
+            );
+            self.laser_cooldown = LASER_COOLDOWN;
+            self.events.push(Event::Laser);
+        }
+    }
+
+    fn update_balls(&mut self, breakage: &mut Breakage) {
+        let (w, h) = self.world_size;
+        let (_, max_speed) = self.ball_speed();
+        let sticky = self.has_effect(PowerUp::Sticky);
+        let paddle = self.paddle();
+        let level = &self.levels[self.level];
+
+        for ball in &mut self.balls {
+            if !ball.active {
+                ball.position = Vector2::new(
+                    self.player.position.x + ball.stuck_offset,
+                    paddle.y - ball.radius as f32,
+                );
+                continue;
+            }
+
+            // Fast balls move in several steps, none longer than the radius,
+            // so that they cannot go through the paddle.
+            let radius = ball.radius as f32;
+            let steps = (ball.speed.norm() / radius).ceil().max(1.0);
+
+            for _ in 0..steps as u32 {
+                sweep_ball(
+                    ball,
+                    1.0 / steps,
+                    &mut self.bricks,
+                    self.brick_size / 2.0,
+                    &mut self.events,
+                    breakage,
+                );
+
+                // Collision logic: ball vs walls
+                if (ball.position.x + radius >= w && ball.speed.x > 0.0)
+                    || (ball.position.x - radius <= 0.0 && ball.speed.x < 0.0)
+                {
+                    ball.speed.x *= -1.0;
+                    self.events.push(Event::Bounce);
+                }
+
+                if ball.position.y - radius <= 0.0 && ball.speed.y < 0.0 {
+                    ball.speed.y *= -1.0;
+                    self.events.push(Event::Bounce);
+                }
+
+                // Collision logic: ball vs player
+                if paddle.check_collision_circle_rec(ball.position, radius) && ball.speed.y > 0.0 {
+                    let offset = ball.position.x - self.player.position.x;
+                    self.combo = 0;
+                    self.events.push(Event::Bounce);
+
+                    if sticky {
+                        ball.active = false;
+                        ball.speed = Vector2::default();
+                        ball.stuck_offset = offset;
+                        break;
+                    }
+
+                    // Balls get faster with every hit, up to the top speed
+                    let speed = (ball.speed.norm() + level.speed_ramp).min(max_speed);
+                    ball.speed = paddle_bounce(
+                        offset / (self.player.size.x / 2.0),
+                        self.player.velocity,
+                        speed,
+                        level,
+                    );
+                }
+            }
+        }
+
+        if self.levels[self.level].ball_collisions {
+            self.collide_balls();
+        }
+
+        self.balls
+            .retain(|ball| ball.position.y + (ball.radius as f32) < h);
+    }
+
+    /// Bounce balls in play off each other. Each ball keeps its speed and only
+    /// has its direction mirrored along the line joining the two centers.
+    fn collide_balls(&mut self) {
+        for i in 1..self.balls.len() {
+            let (head, tail) = self.balls.split_at_mut(i);
+            let ball = &mut tail[0];
+
+            for other in head.iter_mut() {
+                if !ball.active || !other.active {
+                    continue;
+                }
+
+                let delta = ball.position - other.position;
+                let distance = delta.norm();
+                let min_distance = (ball.radius + other.radius) as f32;
+
+                if distance >= min_distance || distance == 0.0 {
+                    continue;
+                }
+
+                let normal = delta / distance;
+
+                // Only bounce balls getting closer, not ones already parting.
+                if (ball.speed - other.speed).dot(&normal) >= 0.0 {
+                    continue;
+                }
+
+                if ball.speed.dot(&normal) < 0.0 {
+                    ball.speed -= 2.0 * ball.speed.dot(&normal) * normal;
+                }
+                if other.speed.dot(&normal) > 0.0 {
+                    other.speed -= 2.0 * other.speed.dot(&normal) * normal;
+                }
+
+                // Push them apart so they do not stay stuck together.
+                let overlap = (min_distance - distance) / 2.0;
+                ball.position += normal * overlap;
+                other.position -= normal * overlap;
+
+                self.events.push(Event::Bounce);
+            }
+        }
+    }
+
+    fn update_lasers(&mut self, breakage: &mut Breakage) {
+        self.laser_cooldown = self.laser_cooldown.saturating_sub(1);
+
+        let half_size = self.brick_size / 2.0;
+
+        self.lasers.retain_mut(|laser| {
+            laser.y -= LASER_SPEED;
+
+            let Some(index) = self.bricks.iter().position(|brick| {
+                (laser.x - brick.position.x).abs() < half_size.x
+                    && (laser.y - brick.position.y).abs() < half_size.y
+            }) else {
+                return laser.y >= 0.0;
+            };
+
+            if !hit_brick(&mut self.bricks[index], &mut self.events, breakage) {
+                self.bricks.remove(index);
+            }
+
+            false
+        });
+    }
+
+    /// Explosive bricks take their neighbors along, possibly setting off
+    /// other explosive bricks.
+    fn detonate(&mut self, breakage: &mut Breakage) {
+        while let Some(center) = breakage.explosions.pop() {
+            self.events.push(Event::Explosion);
+
+            let reach = self.brick_size * 1.5;
+            self.bricks.retain(|brick| {
+                let neighbor = (brick.position.x - center.x).abs() < reach.x
+                    && (brick.position.y - center.y).abs() < reach.y;
+
+                match brick.kind {
+                    _ if !neighbor => return true,
+                    BrickKind::Steel => return true,
+                    BrickKind::Explosive => breakage.explosions.push(brick.position),
+                    BrickKind::Normal(_) | BrickKind::Tough(_) => {}
+                }
+
+                breakage.destroyed.push((brick.position, brick.points()));
+                false
+            });
+        }
+    }
+
+    fn drop_capsules(&mut self, destroyed: &[(Vector2<f32>, u32)]) {
+        for &(position, _) in destroyed {
+            if self.rng.f32() < CAPSULE_CHANCE {
+                let kind = PowerUp::random(&mut self.rng);
+                self.capsules.push(Capsule { position, kind });
+            }
+        }
+    }
+
+    fn update_capsules(&mut self) {
+        let h = self.world_size.1;
+        let mut caught = Vec::new();
+
+        self.capsules.retain_mut(|capsule| {
+            capsule.position.y += CAPSULE_SPEED;
+
+            if (capsule.position.x - self.player.position.x).abs()
+                < (CAPSULE_WIDTH + self.player.size.x) / 2.0
+                && (capsule.position.y - self.player.position.y).abs()
+                    < (CAPSULE_HEIGHT + self.player.size.y) / 2.0
+            {
+                caught.push(capsule.kind);
+                return false;
+            }
+
+            capsule.position.y - CAPSULE_HEIGHT / 2.0 < h
+        });
+
+        for kind in caught {
+            self.score += CAPSULE_POINTS;
+            self.activate(kind);
+        }
+    }
+
+    fn activate(&mut self, kind: PowerUp) {
+        self.events.push(Event::PowerUp);
+
+        match kind {
+            PowerUp::MultiBall => self.split_balls(),
+            PowerUp::ExtraLife => self.player.life = (self.player.life + 1).min(PLAYER_MAX_LIFE),
+            PowerUp::Slow if !self.has_effect(PowerUp::Slow) => {
+                for ball in &mut self.balls {
+                    ball.speed *= SLOW_FACTOR;
+                }
+            }
+            _ => {}
+        }
+
+        if kind.is_timed() {
+            self.effects.retain(|effect| effect.kind != kind);
+            self.effects.push(Effect {
+                kind,
+                ticks_left: EFFECT_TICKS,
+            });
+        }
+    }
+
+    /// Add two balls next to each ball in play, heading 20° apart.
+    fn split_balls(&mut self) {
+        let active: Vec<Ball> = self
+            .balls
+            .iter()
+            .filter(|ball| ball.active)
+            .cloned()
+            .collect();
+
+        for ball in active {
+            for angle in [-20f32, 20.0] {
+                if self.balls.len() >= MAX_BALLS {
+                    return;
+                }
+
+                self.balls.push(Ball {
+                    speed: Rotation2::new(angle.to_radians()) * ball.speed,
+                    ..ball.clone()
+                });
+            }
+        }
+    }
+}
+
+/// Speed of a ball leaving the paddle at `speed`. `hit` is where the ball hit
+/// it, from -1 on the left edge to 1 on the right one, and sets the angle.
+/// The paddle's own `velocity` drags the ball along.
+fn paddle_bounce(hit: f32, velocity: f32, speed: f32, level: &Level) -> Vector2<f32> {
+    let max_angle = level.max_bounce_angle.to_radians();
+    let aimed = hit.clamp(-1.0, 1.0) * max_angle;
+
+    let dragged = Vector2::new(aimed.sin(), -aimed.cos()) * speed
+        + Vector2::new(velocity * level.english, 0.0);
+    let angle = dragged.x.atan2(-dragged.y).clamp(-max_angle, max_angle);
+
+    Vector2::new(angle.sin(), -angle.cos()) * speed
+}
+
+/// Move `ball` by `fraction` of its speed, bouncing off the bricks in the way
+/// in the order it reaches them.
+fn sweep_ball(
+    ball: &mut Ball,
+    fraction: f32,
+    bricks: &mut Vec<Brick>,
+    half_size: Vector2<f32>,
+    events: &mut Vec<Event>,
+    breakage: &mut Breakage,
+) {
+    let radius = ball.radius as f32;
+    let mut remaining = fraction;
+
+    for _ in 0..MAX_BOUNCES {
+        let motion = ball.speed * remaining;
+
+        let contact = bricks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, brick)| {
+                collision::sweep_circle_box(
+                    ball.position,
+                    motion,
+                    radius,
+                    brick.position,
+                    half_size,
+                )
+                .map(|contact| (index, contact))
+            })
+            .min_by(|(_, a), (_, b)| a.time.total_cmp(&b.time));
+
+        let Some((index, contact)) = contact else {
+            ball.position += motion;
+            return;
+        };
+
+        ball.position += motion * contact.time;
+        ball.speed -= 2.0 * ball.speed.dot(&contact.normal) * contact.normal;
+        remaining *= 1.0 - contact.time;
+
+        if !hit_brick(&mut bricks[index], events, breakage) {
+            bricks.remove(index);
+        }
+    }
+}
+
+/// Apply one hit to `brick`, returns whether it is still standing.
+fn hit_brick(brick: &mut Brick, events: &mut Vec<Event>, breakage: &mut Breakage) -> bool {
+    match brick.kind {
+        BrickKind::Steel => {
+            events.push(Event::Steel);
+            return true;
+        }
+        BrickKind::Tough(_) if brick.hits > 1 => {
+            brick.hits -= 1;
+            events.push(Event::Hit);
+            return true;
+        }
+        BrickKind::Explosive => breakage.explosions.push(brick.position),
+        BrickKind::Normal(_) | BrickKind::Tough(_) => events.push(Event::Destroyed),
+    }
+
+    breakage.destroyed.push((brick.position, brick.points()));
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HALF_SIZE: Vector2<f32> = Vector2::new(20.0, 10.0);
+
+    fn ball(position: (f32, f32), speed: (f32, f32)) -> Ball {
+        Ball {
+            position: Vector2::new(position.0, position.1),
+            speed: Vector2::new(speed.0, speed.1),
+            radius: BALL_RADIUS,
+            active: true,
+            ..Ball::default()
+        }
+    }
+
+    fn sweep(ball: &mut Ball, bricks: &mut Vec<Brick>) -> (Vec<Event>, Breakage) {
+        let mut events = Vec::new();
+        let mut breakage = Breakage::default();
+
+        sweep_ball(ball, 1.0, bricks, HALF_SIZE, &mut events, &mut breakage);
+
+        (events, breakage)
+    }
+
+    /// Steel bricks at `x` on both sides of the origin, the ball has
+    /// `2 * (x - HALF_SIZE.x - BALL_RADIUS)` pixels to go between them.
+    fn corridor(x: f32) -> Vec<Brick> {
+        [-x, x]
+            .map(|x| Brick::new(BrickKind::Steel, Vector2::new(x, 0.0)))
+            .into()
+    }
+
+    /// A game on a one brick level, with the ball about to hit the middle of
+    /// the paddle at `speed`.
+    fn falling_on_paddle(speed: f32) -> Game {
+        let mut game = Game::new(vec![Level::parse("name: X\n---\nr\n").unwrap()], 1);
+        game.init((800.0, 480.0));
+        game.ready_ticks = 0;
+        game.serving = false;
+
+        let paddle = game.paddle();
+        game.balls = vec![ball(
+            (game.player.position.x, paddle.y - BALL_RADIUS as f32 - 1.0),
+            (0.0, speed),
+        )];
+
+        game
+    }
+
+    #[test]
+    fn paddle_bounce_keeps_the_speed_and_goes_up() {
+        let level = Level::classic();
+        let max_angle = level.max_bounce_angle.to_radians();
+
+        for hit in [-1.5, -1.0, -0.3, 0.0, 0.5, 1.0, 2.0] {
+            for velocity in [-1000.0, -8.0, 0.0, 3.0, 1000.0] {
+                let speed = paddle_bounce(hit, velocity, 7.0, &level);
+                let angle = speed.x.atan2(-speed.y);
+
+                assert!((speed.norm() - 7.0).abs() < 1e-4, "{hit} {velocity}");
+                assert!(speed.y < 0.0, "{hit} {velocity}");
+                assert!(angle.abs() <= max_angle + 1e-6, "{hit} {velocity}");
+            }
+        }
+    }
+
+    #[test]
+    fn paddle_velocity_cannot_push_past_the_max_angle() {
+        let level = Level {
+            english: 10.0,
+            ..Level::classic()
+        };
+        let max_angle = level.max_bounce_angle.to_radians();
+
+        let speed = paddle_bounce(0.0, 50.0, 5.0, &level);
+
+        assert!((speed.x.atan2(-speed.y) - max_angle).abs() < 1e-6);
+        assert!((speed.norm() - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn paddle_hits_ramp_the_speed_up_to_the_max() {
+        let level = Level::classic();
+
+        let mut game = falling_on_paddle(level.ball_speed);
+        game.update(&Input::default());
+        let speed = game.balls[0].speed.norm();
+        assert!((speed - level.ball_speed - level.speed_ramp).abs() < 1e-4);
+
+        // Already close to the top speed, it does not go past it
+        let mut game = falling_on_paddle(level.max_ball_speed - level.speed_ramp / 2.0);
+        game.update(&Input::default());
+        assert!((game.balls[0].speed.norm() - level.max_ball_speed).abs() < 1e-4);
+        assert!(game.balls[0].speed.y < 0.0);
+
+        let mut game = falling_on_paddle(level.max_ball_speed);
+        game.update(&Input::default());
+        assert!((game.balls[0].speed.norm() - level.max_ball_speed).abs() < 1e-4);
+    }
+
+    #[test]
+    fn clearing_the_level_as_the_last_ball_drains_costs_no_life() {
+        let level = |name| Level::parse(&format!("name: {name}\n---\nr\n")).unwrap();
+        let mut game = Game::new(vec![level("First"), level("Second")], 1);
+        game.init((800.0, 480.0));
+        game.ready_ticks = 0;
+        game.serving = false;
+
+        // A laser breaks the last brick while the only ball drains
+        let brick = game.bricks[0].position;
+        game.lasers = vec![Vector2::new(brick.x, brick.y + LASER_SPEED)];
+        game.balls = vec![ball((400.0, 480.0 - BALL_RADIUS as f32 - 1.0), (0.0, 6.0))];
+        // Keep the paddle out of the way
+        game.player.position.x = 40.0;
+
+        let life = game.player.life;
+        game.update(&Input::default());
+
+        assert_eq!(game.level, 1);
+        assert_eq!(game.player.life, life);
+        assert_eq!(game.lost_ticks, 0);
+        assert!(!game.take_events().contains(&Event::LifeLost));
+    }
+
+    #[test]
+    fn only_earliest_of_two_bricks_is_hit() {
+        // Both bricks are within this step, the ball bounces off the first
+        let mut bricks = vec![
+            Brick::new(BrickKind::Normal(Color::RED), Vector2::new(100.0, 0.0)),
+            Brick::new(BrickKind::Normal(Color::BLUE), Vector2::new(50.0, 0.0)),
+        ];
+        let mut ball = ball((0.0, 0.0), (150.0, 0.0));
+
+        let (events, breakage) = sweep(&mut ball, &mut bricks);
+
+        assert_eq!(events, [Event::Destroyed]);
+        assert_eq!(breakage.destroyed, [(Vector2::new(50.0, 0.0), 50)]);
+        assert_eq!(bricks.len(), 1);
+        assert_eq!(bricks[0].position, Vector2::new(100.0, 0.0));
+        assert_eq!(ball.speed, Vector2::new(-150.0, 0.0));
+        // 23 pixels to the brick, then 127 back
+        assert!(
+            (ball.position.x + 104.0).abs() < 1e-3,
+            "{}",
+            ball.position.x
+        );
+    }
+
+    #[test]
+    fn bounces_several_times_in_one_step() {
+        let mut bricks = corridor(50.0);
+        let mut ball = ball((0.0, 0.0), (80.0, 0.0));
+
+        let (events, _) = sweep(&mut ball, &mut bricks);
+
+        // 23 pixels right, 46 left, then 11 right again
+        assert_eq!(events, [Event::Steel, Event::Steel]);
+        assert_eq!(ball.speed, Vector2::new(80.0, 0.0));
+        assert!((ball.position.x + 12.0).abs() < 1e-3, "{}", ball.position.x);
+    }
+
+    #[test]
+    fn bounces_are_capped() {
+        let mut bricks = corridor(50.0);
+        let mut ball = ball((0.0, 0.0), (1000.0, 0.0));
+
+        let (events, _) = sweep(&mut ball, &mut bricks);
+
+        // The rest of the motion is dropped, the ball stays in the corridor
+        assert_eq!(events, [Event::Steel; MAX_BOUNCES]);
+        assert!(ball.position.x.abs() <= 23.0 + 1e-3, "{}", ball.position.x);
+        assert_eq!(bricks.len(), 2);
+    }
+}
@@ -0,0 +1,85 @@
+use nalgebra::Vector2;
+use raylib::prelude::*;
+
+use super::TICKS_PER_SECOND;
+
+/// Chance for a destroyed brick to release a capsule.
+pub(crate) const CAPSULE_CHANCE: f32 = 0.15;
+pub(crate) const CAPSULE_SPEED: f32 = 2.0;
+pub(crate) const CAPSULE_WIDTH: f32 = 36.0;
+pub(crate) const CAPSULE_HEIGHT: f32 = 14.0;
+/// How long timed power-ups last.
+pub(crate) const EFFECT_TICKS: u32 = 15 * TICKS_PER_SECOND;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PowerUp {
+    /// Wider paddle, timed.
+    Expand,
+    /// Every ball in play splits in three.
+    MultiBall,
+    /// The paddle fires lasers upward, timed.
+    Laser,
+    /// Slower balls, timed.
+    Slow,
+    /// Balls stick to the paddle until launched again, timed.
+    Sticky,
+    ExtraLife,
+}
+
+impl PowerUp {
+    const ALL: [PowerUp; 6] = [
+        PowerUp::Expand,
+        PowerUp::MultiBall,
+        PowerUp::Laser,
+        PowerUp::Slow,
+        PowerUp::Sticky,
+        PowerUp::ExtraLife,
+    ];
+
+    pub fn random(rng: &mut fastrand::Rng) -> Self {
+        Self::ALL[rng.usize(..Self::ALL.len())]
+    }
+
+    /// Whether the power-up wears off after [`EFFECT_TICKS`].
+    pub fn is_timed(self) -> bool {
+        matches!(
+            self,
+            PowerUp::Expand | PowerUp::Laser | PowerUp::Slow | PowerUp::Sticky
+        )
+    }
+
+    /// Capsule colors and letters follow the original arcade game.
+    pub fn color(self) -> Color {
+        match self {
+            PowerUp::Expand => Color::BLUE,
+            PowerUp::MultiBall => Color::SKYBLUE,
+            PowerUp::Laser => Color::RED,
+            PowerUp::Slow => Color::ORANGE,
+            PowerUp::Sticky => Color::GREEN,
+            PowerUp::ExtraLife => Color::GRAY,
+        }
+    }
+
+    pub fn letter(self) -> &'static str {
+        match self {
+            PowerUp::Expand => "E",
+            PowerUp::MultiBall => "D",
+            PowerUp::Laser => "L",
+            PowerUp::Slow => "S",
+            PowerUp::Sticky => "C",
+            PowerUp::ExtraLife => "P",
+        }
+    }
+}
+
+/// A falling power-up, caught by touching it with the paddle.
+pub struct Capsule {
+    pub position: Vector2<f32>,
+    pub kind: PowerUp,
+}
+
+/// A timed power-up currently running.
+pub struct Effect {
+    pub kind: PowerUp,
+    pub ticks_left: u32,
+}
@@ -0,0 +1,266 @@
+use common::input::Bindings;
+use nalgebra::Vector2;
+use raylib::{core::text::measure_text, prelude::*};
+
+use super::{powerup::*, *};
+
+impl Game {
+    /// Draw the game, telling the player what to press by the `bindings`.
+    pub fn draw(&self, rl: &RaylibHandle, d: &RaylibDrawHandle, bindings: &Bindings<Action>) {
+        d.draw_fps(10, 10);
+
+        let (w, h) = (rl.get_screen_width() as f32, rl.get_screen_height() as f32);
+
+        d.clear_background(Color::RAYWHITE);
+
+        if !self.game_over {
+            // Draw player bar, tinted by the running power-ups
+            let paddle_color = if self.has_effect(PowerUp::Laser) {
+                PowerUp::Laser.color()
+            } else if self.has_effect(PowerUp::Sticky) {
+                PowerUp::Sticky.color()
+            } else {
+                Color::BLACK
+            };
+
+            // The paddle blinks and shrinks away when a life is lost
+            let paddle_width = self.player.size.x
+                * if self.lost_ticks > 0 {
+                    self.lost_ticks as f32 / LIFE_LOST_TICKS as f32
+                } else {
+                    1.0
+                };
+            if self.lost_ticks % 16 < 8 {
+                d.draw_rectangle(
+                    (self.player.position.x - paddle_width / 2.0) as i32,
+                    (self.player.position.y - self.player.size.y / 2.0) as i32,
+                    paddle_width as i32,
+                    self.player.size.y as i32,
+                    paddle_color,
+                );
+            }
+
+            // Draw player lives
+            for i in 0..self.player.life {
+                d.draw_rectangle(20 + 40 * i, h as i32 - 30, 35, 10, Color::LIGHTGRAY);
+            }
+
+            // Draw score and combo
+            let score = format!("SCORE: {}", self.score);
+            d.draw_text(
+                &score,
+                (w / 2.0) as i32 - measure_text(&score, 20) / 2,
+                h as i32 - 35,
+                20,
+                Color::GRAY,
+            );
+            if self.combo > 1 {
+                d.draw_text(
+                    &format!("x{}", self.combo.min(MAX_COMBO)),
+                    (w / 2.0) as i32 + measure_text(&score, 20) / 2 + 10,
+                    h as i32 - 35,
+                    20,
+                    Color::MAROON,
+                );
+            }
+
+            // Draw level name and time
+            let level = &self.levels[self.level];
+            let seconds = self.level_ticks / TICKS_PER_SECOND;
+            let mut status = format!(
+                "LEVEL {}: {}   {}:{:02}",
+                self.level + 1,
+                level.name,
+                seconds / 60,
+                seconds % 60
+            );
+            if let Some(par_time) = level.par_time {
+                status += &format!(" / PAR {}:{:02}", par_time / 60, par_time % 60);
+            }
+            d.draw_text(
+                &status,
+                w as i32 - measure_text(&status, 10) - 20,
+                h as i32 - 30,
+                10,
+                Color::GRAY,
+            );
+
+            // Draw balls
+            for ball in &self.balls {
+                d.draw_circle_v(ball.position, ball.radius as f32, Color::MAROON);
+            }
+
+            // Draw where the balls are going to be served
+            if self.serving && self.ready_ticks == 0 {
+                let angle = self.serve_angle();
+                let aim = Vector2::new(angle.sin(), -angle.cos());
+
+                for ball in &self.balls {
+                    d.draw_line_v(
+                        ball.position + aim * (ball.radius as f32 + 4.0),
+                        ball.position + aim * (ball.radius as f32 + 40.0),
+                        Color::DARKGRAY,
+                    );
+                }
+
+                let serve = format!("PRESS [{}] TO SERVE", bindings.describe(Action::Launch));
+                d.draw_text(
+                    &serve,
+                    (w / 2.0) as i32 - measure_text(&serve, 20) / 2,
+                    (h / 2.0) as i32 + 40,
+                    20,
+                    Color::GRAY,
+                );
+            }
+
+            // Draw lasers
+            for laser in &self.lasers {
+                d.draw_rectangle(laser.x as i32 - 1, laser.y as i32 - 6, 3, 12, Color::RED);
+            }
+
+            // Draw capsules
+            for capsule in &self.capsules {
+                let (x, y) = (
+                    (capsule.position.x - CAPSULE_WIDTH / 2.0) as i32,
+                    (capsule.position.y - CAPSULE_HEIGHT / 2.0) as i32,
+                );
+                let letter = capsule.kind.letter();
+
+                d.draw_rectangle(
+                    x,
+                    y,
+                    CAPSULE_WIDTH as i32,
+                    CAPSULE_HEIGHT as i32,
+                    capsule.kind.color(),
+                );
+                d.draw_text(
+                    letter,
+                    capsule.position.x as i32 - measure_text(letter, 10) / 2,
+                    y + 2,
+                    10,
+                    Color::WHITE,
+                );
+            }
+
+            // Draw bricks
+            for brick in &self.bricks {
+                let (x, y) = (
+                    (brick.position.x - self.brick_size.x / 2.0) as i32,
+                    (brick.position.y - self.brick_size.y / 2.0) as i32,
+                );
+                let (width, height) = (self.brick_size.x as i32, self.brick_size.y as i32);
+
+                d.draw_rectangle(x, y, width, height, brick.color());
+
+                match brick.kind {
+                    BrickKind::Steel => d.draw_rectangle_lines(
+                        x + 2,
+                        y + 2,
+                        width - 4,
+                        height - 4,
+                        Color::LIGHTGRAY,
+                    ),
+                    BrickKind::Explosive => d.draw_rectangle(
+                        x + width / 4,
+                        y + height / 4,
+                        width / 2,
+                        height / 2,
+                        Color::MAROON,
+                    ),
+                    _ => {}
+                }
+            }
+
+            if self.ready_ticks > 0 {
+                let title = format!("LEVEL {}: {}", self.level + 1, level.name);
+                let countdown = (self.ready_ticks / TICKS_PER_SECOND + 1).to_string();
+
+                d.draw_text(
+                    &title,
+                    (w / 2.0) as i32 - measure_text(&title, 20) / 2,
+                    (h / 2.0) as i32 - 80,
+                    20,
+                    Color::DARKGRAY,
+                );
+                d.draw_text(
+                    "READY",
+                    (w / 2.0) as i32 - measure_text("READY", 40) / 2,
+                    (h / 2.0) as i32 - 40,
+                    40,
+                    Color::GRAY,
+                );
+                d.draw_text(
+                    &countdown,
+                    (w / 2.0) as i32 - measure_text(&countdown, 40) / 2,
+                    (h / 2.0) as i32 + 10,
+                    40,
+                    Color::MAROON,
+                );
+            }
+
+            if self.pause {
+                d.draw_text(
+                    "Game Pause",
+                    (w / 2.0) as i32 - measure_text("Game Paused", 40) / 2,
+                    (h / 2.0 - 40.0) as i32,
+                    40,
+                    Color::GRAY,
+                );
+            }
+        } else {
+            // Tell a cleared campaign from a run out of lives
+            let (title, title_color, detail) = if self.cleared {
+                (
+                    "CAMPAIGN CLEARED!",
+                    Color::DARKGREEN,
+                    format!("ALL {} LEVELS BROKEN THROUGH", self.levels.len()),
+                )
+            } else {
+                (
+                    "GAME OVER",
+                    Color::MAROON,
+                    format!(
+                        "OUT OF LIVES ON LEVEL {}: {}",
+                        self.level + 1,
+                        self.levels[self.level].name
+                    ),
+                )
+            };
+            d.draw_text(
+                title,
+                (w / 2.0) as i32 - measure_text(title, 40) / 2,
+                (h / 2.0) as i32 - 190,
+                40,
+                title_color,
+            );
+            d.draw_text(
+                &detail,
+                (w / 2.0) as i32 - measure_text(&detail, 20) / 2,
+                (h / 2.0) as i32 - 140,
+                20,
+                Color::GRAY,
+            );
+
+            let score = format!("SCORE: {}", self.score);
+            d.draw_text(
+                &score,
+                (w / 2.0) as i32 - measure_text(&score, 40) / 2,
+                (h / 2.0) as i32 - 110,
+                40,
+                Color::DARKGRAY,
+            );
+
+            let again = format!(
+                "PRESS [{}] TO PLAY AGAIN",
+                bindings.describe(Action::Restart)
+            );
+            d.draw_text(
+                &again,
+                (w / 2.0) as i32 - measure_text(&again, 20) / 2,
+                (h / 2.0) as i32 - 50,
+                20,
+                Color::GRAY,
+            );
+        }
+    }
+}
@@ -1,11 +1,29 @@
 use assets::Assets;
-use game::Game;
-use raylib::prelude::RaylibAudio;
+use common::{
+    input::{Actions, Bindings},
+    scoreboard::ScoreBoard,
+};
+use game::{campaign_checksum, Action, Control, Event, Game, Input, Level, LevelError};
+use options::{Options, CONTROLS};
+use raylib::prelude::{Color, RaylibAudio};
+use replay::Replay;
 
 mod assets;
 mod game;
+mod options;
+mod replay;
+
+const LEVELS_DIR: &str = "assets/levels";
 
 fn main() {
+    let options = match Options::from_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("arkanoid: {err}");
+            std::process::exit(2);
+        }
+    };
+
     let rl = raylib::init()
         .title("Arkanoid")
         .width(800)
@@ -16,13 +34,155 @@ fn main() {
     rl.set_target_fps(60);
     raudio.set_master_volume(0.5);
 
-    let mut game = Game::default();
+    let playback = options
+        .replay
+        .as_ref()
+        .map(|path| match Replay::load(path) {
+            Ok(replay) => replay,
+            Err(err) => {
+                eprintln!("arkanoid: cannot load replay {}: {err}", path.display());
+                std::process::exit(1);
+            }
+        });
+
+    let world_size = match &playback {
+        Some(replay) => replay.world_size,
+        None => (rl.get_screen_width() as f32, rl.get_screen_height() as f32),
+    };
+
+    let levels = match Level::load_campaign(LEVELS_DIR) {
+        Ok(levels) => levels,
+        Err(LevelError::Io(_, err)) if err.kind() == std::io::ErrorKind::NotFound => {
+            eprintln!("arkanoid: {LEVELS_DIR} not found, playing the classic level");
+            vec![Level::classic()]
+        }
+        Err(err) => {
+            eprintln!("arkanoid: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    // Edited levels would play out differently from the recording
+    let campaign = campaign_checksum(&levels);
+    if let (Some(path), Some(replay)) = (&options.replay, &playback) {
+        if replay.campaign != campaign {
+            eprintln!(
+                "arkanoid: cannot play replay {}: it was recorded with other levels",
+                path.display()
+            );
+            std::process::exit(1);
+        }
+    }
+
+    let seed = match (&playback, options.seed) {
+        (Some(replay), _) => replay.seed,
+        (None, Some(seed)) => seed,
+        (None, None) => fastrand::u64(..),
+    };
+
+    let bindings = match &options.bindings {
+        Some(path) => match Bindings::load(path) {
+            Ok(bindings) => bindings,
+            Err(err) => {
+                eprintln!("arkanoid: cannot load bindings {}: {err}", path.display());
+                std::process::exit(1);
+            }
+        },
+        None => Bindings::load_config("arkanoid"),
+    };
+
+    // The command line overrides the setting of the bindings file
+    let control = match (options.control, bindings.setting("control")) {
+        (Some(control), _) => control,
+        (None, Some(name)) => Control::parse(name).unwrap_or_else(|| {
+            eprintln!("arkanoid: invalid control '{name}' in the bindings, {CONTROLS}");
+            Control::default()
+        }),
+        (None, None) => Control::default(),
+    };
+    let mut actions = Actions::<Action>::new(bindings);
+
+    let mut game = Game::new(levels, seed);
     let assets = Assets::load(&raudio);
+    let mut scoreboard = ScoreBoard::load("arkanoid");
+
+    game.init(world_size);
+    println!("arkanoid: seed {}", game.seed());
 
-    game.init(&rl);
+    let mut recording = options
+        .record
+        .as_ref()
+        .map(|_| Replay::new(game.seed(), campaign, world_size));
+    let mut replayed_inputs = playback.as_ref().map(|replay| replay.inputs().peekable());
+
+    // Relative motion needs the cursor to stay in the window
+    if control == Control::Relative && replayed_inputs.is_none() {
+        rl.disable_cursor();
+    }
 
     while !rl.window_should_close() {
-        game.update(&rl, &raudio, &assets);
-        rl.begin_drawing(|d| game.draw(&rl, &d));
+        actions.update(&rl);
+
+        // The keyboard goes to the name prompt while it is up, the game
+        // still ticks so that recordings stay in sync.
+        let input = match &mut replayed_inputs {
+            Some(inputs) => inputs.next().unwrap_or_default(),
+            None if scoreboard.is_entering_name() => {
+                scoreboard.update(&rl, actions.is_pressed(Action::Restart));
+                Input::default()
+            }
+            None => Input::poll(&rl, &actions, control),
+        };
+
+        if let Some(recording) = &mut recording {
+            recording.push(input);
+        }
+
+        game.update(&input);
+
+        for event in game.take_events() {
+            match event {
+                Event::Bounce => assets.play_bounce(&raudio),
+                Event::Destroyed => assets.play_destroyed(&raudio),
+                Event::Hit => assets.play_hit(&raudio),
+                Event::Steel => assets.play_steel(&raudio),
+                Event::Explosion => assets.play_explosive(&raudio),
+                Event::PowerUp => assets.play_powerup(&raudio),
+                Event::Laser => assets.play_laser(&raudio),
+                Event::LifeLost => assets.play_life_lost(&raudio),
+                // Replayed sessions do not make it in the high scores.
+                Event::GameOver { score } if replayed_inputs.is_none() => scoreboard.submit(score),
+                Event::GameOver { .. } => {}
+            }
+        }
+
+        rl.begin_drawing(|d| {
+            game.draw(&rl, &d, actions.bindings());
+
+            if game.is_over() {
+                scoreboard.draw(
+                    &d,
+                    (rl.get_screen_width(), rl.get_screen_height()),
+                    &actions.bindings().describe(Action::Restart),
+                );
+            }
+
+            // Make it obvious that the session on screen is not live.
+            if let Some(inputs) = &mut replayed_inputs {
+                let label = if inputs.peek().is_some() {
+                    "REPLAY"
+                } else {
+                    "REPLAY ENDED"
+                };
+                d.draw_text(label, 10, 30, 20, Color::MAROON);
+            }
+        });
+    }
+
+    if let (Some(path), Some(recording)) = (&options.record, &recording) {
+        match recording.save(path) {
+            Ok(()) => println!("arkanoid: replay saved to {}", path.display()),
+            Err(err) => eprintln!("arkanoid: cannot save replay {}: {err}", path.display()),
+        }
     }
 }
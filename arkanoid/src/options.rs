@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use crate::game::Control;
+
+/// What `--control` and the `control` setting accept.
+pub const CONTROLS: &str = "expected 'keyboard', 'mouse', 'relative' or 'analog'";
+
+/// Command line options.
+#[derive(Default)]
+pub struct Options {
+    /// Seed for capsule drops, random when not given.
+    pub seed: Option<u64>,
+    /// How the paddle is steered besides the bound actions, overriding the
+    /// `control` setting of the bindings file.
+    pub control: Option<Control>,
+    /// Read the bindings from this file instead of the user config directory.
+    pub bindings: Option<PathBuf>,
+    /// Record the session inputs to this file.
+    pub record: Option<PathBuf>,
+    /// Play back a previously recorded session instead of reading the keyboard.
+    pub replay: Option<PathBuf>,
+}
+
+impl Options {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().ok_or("--seed expects a value")?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("invalid seed '{value}'"))?;
+                    options.seed = Some(seed);
+                }
+                "--control" => {
+                    let value = args.next().ok_or("--control expects a device")?;
+                    let control = Control::parse(&value)
+                        .ok_or_else(|| format!("invalid control '{value}', {CONTROLS}"))?;
+                    options.control = Some(control);
+                }
+                "--bindings" => {
+                    let path = args.next().ok_or("--bindings expects a file")?;
+                    options.bindings = Some(path.into());
+                }
+                "--record" => {
+                    let path = args.next().ok_or("--record expects a file")?;
+                    options.record = Some(path.into());
+                }
+                "--replay" => {
+                    let path = args.next().ok_or("--replay expects a file")?;
+                    options.replay = Some(path.into());
+                }
+                _ => return Err(format!("unknown argument '{arg}'")),
+            }
+        }
+
+        if options.replay.is_some() && options.seed.is_some() {
+            return Err(
+                "--seed cannot be used with --replay, the seed is stored in the replay".into(),
+            );
+        }
+
+        Ok(options)
+    }
+}
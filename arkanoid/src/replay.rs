@@ -0,0 +1,224 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::game::Input;
+
+const MAGIC: &[u8; 4] = b"ARKA";
+/// Bumped whenever the file layout or the simulation changes, including the
+/// order of random draws: older replays would load fine and then play out
+/// differently.
+const VERSION: u8 = 5;
+/// Smallest world width and height a replay may ask for, smaller ones leave
+/// no room for the paddle to move or the bricks to be hit.
+const MIN_WORLD_SIZE: f32 = 300.0;
+
+/// A recorded session: the seed, campaign checksum and world size the game was
+/// started with and the inputs of every tick.
+///
+/// On disk, after the header, inputs are run-length encoded as runs of eight
+/// bytes: the key bits, the mouse target (`i16::MIN` for none), the mouse
+/// motion, the stick position and the repeat count. Numbers are
+/// little-endian, the stick position takes a single byte.
+pub struct Replay {
+    pub seed: u64,
+    /// Levels the session was played on, see [`campaign_checksum`].
+    ///
+    /// [`campaign_checksum`]: crate::game::campaign_checksum
+    pub campaign: u64,
+    pub world_size: (f32, f32),
+    inputs: Vec<Input>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, campaign: u64, world_size: (f32, f32)) -> Self {
+        Self {
+            seed,
+            campaign,
+            world_size,
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Append the inputs of one tick.
+    pub fn push(&mut self, input: Input) {
+        self.inputs.push(input);
+    }
+
+    /// Recorded inputs, one per tick.
+    pub fn inputs(&self) -> impl Iterator<Item = Input> + '_ {
+        self.inputs.iter().copied()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&self.seed.to_le_bytes())?;
+        writer.write_all(&self.campaign.to_le_bytes())?;
+        writer.write_all(&self.world_size.0.to_le_bytes())?;
+        writer.write_all(&self.world_size.1.to_le_bytes())?;
+
+        let mut inputs = self.inputs.iter().peekable();
+
+        while let Some(input) = inputs.next() {
+            let mut count: u16 = 1;
+
+            while count < u16::MAX && inputs.next_if_eq(&input).is_some() {
+                count += 1;
+            }
+
+            writer.write_all(&[input.to_bits()])?;
+            writer.write_all(&input.target.unwrap_or(i16::MIN).to_le_bytes())?;
+            writer.write_all(&input.motion.to_le_bytes())?;
+            writer.write_all(&input.axis.to_le_bytes())?;
+            writer.write_all(&count.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not an arkanoid replay"));
+        }
+
+        let mut version = [0; 1];
+        reader.read_exact(&mut version)?;
+        if version[0] != VERSION {
+            return Err(invalid_data(format!(
+                "unsupported replay version {}",
+                version[0]
+            )));
+        }
+
+        let mut seed = [0; 8];
+        reader.read_exact(&mut seed)?;
+
+        let mut campaign = [0; 8];
+        reader.read_exact(&mut campaign)?;
+
+        let mut width = [0; 4];
+        let mut height = [0; 4];
+        reader.read_exact(&mut width)?;
+        reader.read_exact(&mut height)?;
+        let (width, height) = (f32::from_le_bytes(width), f32::from_le_bytes(height));
+        let valid = |size: f32| size.is_finite() && size >= MIN_WORLD_SIZE;
+        if !valid(width) || !valid(height) {
+            return Err(invalid_data(format!("invalid world size {width}x{height}")));
+        }
+
+        let mut replay = Self::new(
+            u64::from_le_bytes(seed),
+            u64::from_le_bytes(campaign),
+            (width, height),
+        );
+
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)?;
+
+        if body.len() % 8 != 0 {
+            return Err(invalid_data("truncated replay"));
+        }
+
+        for run in body.chunks_exact(8) {
+            let target = i16::from_le_bytes([run[1], run[2]]);
+            let input = Input {
+                target: (target != i16::MIN).then_some(target),
+                motion: i16::from_le_bytes([run[3], run[4]]),
+                axis: i8::from_le_bytes([run[5]]),
+                ..Input::from_bits(run[0])
+            };
+            let count = u16::from_le_bytes([run[6], run[7]]);
+
+            replay
+                .inputs
+                .extend(std::iter::repeat_n(input, count as usize));
+        }
+
+        Ok(replay)
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut replay = Replay::new(7, 0xdead_beef, (800.0, 480.0));
+        let inputs = [
+            Input::default(),
+            Input {
+                left: true,
+                target: Some(-3),
+                motion: 12,
+                axis: -127,
+                ..Input::default()
+            },
+            Input {
+                launch: true,
+                target: Some(640),
+                ..Input::default()
+            },
+        ];
+        for input in inputs.iter().flat_map(|input| [*input; 3]) {
+            replay.push(input);
+        }
+
+        let mut file = Vec::new();
+        replay.write_to(&mut file).unwrap();
+        let loaded = Replay::read_from(file.as_slice()).unwrap();
+
+        assert_eq!(loaded.seed, 7);
+        assert_eq!(loaded.campaign, 0xdead_beef);
+        assert_eq!(loaded.world_size, (800.0, 480.0));
+        assert!(loaded.inputs().eq(replay.inputs()));
+    }
+
+    #[test]
+    fn older_versions_are_rejected() {
+        let mut file = Vec::new();
+        Replay::new(7, 0, (800.0, 480.0))
+            .write_to(&mut file)
+            .unwrap();
+        file[MAGIC.len()] = VERSION - 1;
+
+        let err = Replay::read_from(file.as_slice()).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn bad_world_sizes_are_rejected() {
+        for world_size in [
+            (0.0, 480.0),
+            (-800.0, 480.0),
+            (f32::NAN, 480.0),
+            (800.0, f32::INFINITY),
+            (800.0, 100.0),
+        ] {
+            let mut file = Vec::new();
+            Replay::new(7, 0, world_size).write_to(&mut file).unwrap();
+
+            let err = Replay::read_from(file.as_slice()).err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{world_size:?}");
+        }
+    }
+}
@@ -1,8 +1,17 @@
 use raylib::prelude::*;
 
+use crate::game::{SaucerKind, Weapon};
+
 pub struct Assets<'rl> {
     pub explosion_sounds: Vec<Sound<'rl>>,
-    pub shoot_sound: Option<Sound<'rl>>,
+    /// One sound per weapon, in [`Weapon::ALL`] order.
+    pub shoot_sounds: Vec<Option<Sound<'rl>>>,
+    pub extra_life_sound: Option<Sound<'rl>>,
+    pub large_saucer_sound: Option<Sound<'rl>>,
+    pub small_saucer_sound: Option<Sound<'rl>>,
+    pub saucer_shot_sound: Option<Sound<'rl>>,
+    pub hyperspace_sound: Option<Sound<'rl>>,
+    pub shield_sound: Option<Sound<'rl>>,
 }
 
 impl<'rl> Assets<'rl> {
@@ -14,21 +23,86 @@ impl<'rl> Assets<'rl> {
             .map(|o| o.unwrap())
             .collect();
 
-        let shoot_sound = Sound::load_sound(raudio, "assets/laserShoot.wav").ok();
+        let shoot_sounds = Weapon::ALL
+            .iter()
+            .map(|weapon| {
+                let file = match weapon {
+                    Weapon::Blaster => "assets/laserShoot.wav",
+                    Weapon::Spread => "assets/spread.wav",
+                    Weapon::Rapid => "assets/rapid.wav",
+                    Weapon::Laser => "assets/beam.wav",
+                    Weapon::Homing => "assets/missile.wav",
+                };
+                Sound::load_sound(raudio, file).ok()
+            })
+            .collect();
+        let extra_life_sound = Sound::load_sound(raudio, "assets/powerup.wav").ok();
+        let large_saucer_sound = Sound::load_sound(raudio, "assets/saucerLarge.wav").ok();
+        let small_saucer_sound = Sound::load_sound(raudio, "assets/saucerSmall.wav").ok();
+        let saucer_shot_sound = Sound::load_sound(raudio, "assets/saucerShoot.wav").ok();
+        let hyperspace_sound = Sound::load_sound(raudio, "assets/hyperspace.wav").ok();
+        let shield_sound = Sound::load_sound(raudio, "assets/shield.wav").ok();
 
         Self {
             explosion_sounds,
-            shoot_sound,
+            shoot_sounds,
+            extra_life_sound,
+            large_saucer_sound,
+            small_saucer_sound,
+            saucer_shot_sound,
+            hyperspace_sound,
+            shield_sound,
         }
     }
 
-    pub fn play_explosion(&self, raudio: &RaylibAudio) {
-        // Play a random explosion.
+    pub fn play_explosion(&self, raudio: &RaylibAudio, variant: u32) {
+        // Play the explosion picked by the game.
         if self.explosion_sounds.is_empty() {
             return;
         }
 
-        let sound_index = fastrand::usize(0..self.explosion_sounds.len());
+        let sound_index = variant as usize % self.explosion_sounds.len();
         raudio.play_sound(&self.explosion_sounds[sound_index]);
     }
+
+    pub fn play_shoot(&self, raudio: &RaylibAudio, weapon: Weapon) {
+        if let Some(shoot_sound) = &self.shoot_sounds[weapon as usize] {
+            raudio.play_sound(shoot_sound);
+        }
+    }
+
+    pub fn play_saucer(&self, raudio: &RaylibAudio, kind: SaucerKind) {
+        let saucer_sound = match kind {
+            SaucerKind::Large => &self.large_saucer_sound,
+            SaucerKind::Small => &self.small_saucer_sound,
+        };
+
+        if let Some(saucer_sound) = saucer_sound {
+            raudio.play_sound(saucer_sound);
+        }
+    }
+
+    pub fn play_saucer_shot(&self, raudio: &RaylibAudio) {
+        if let Some(saucer_shot_sound) = &self.saucer_shot_sound {
+            raudio.play_sound(saucer_shot_sound);
+        }
+    }
+
+    pub fn play_hyperspace(&self, raudio: &RaylibAudio) {
+        if let Some(hyperspace_sound) = &self.hyperspace_sound {
+            raudio.play_sound(hyperspace_sound);
+        }
+    }
+
+    pub fn play_shield(&self, raudio: &RaylibAudio) {
+        if let Some(shield_sound) = &self.shield_sound {
+            raudio.play_sound(shield_sound);
+        }
+    }
+
+    pub fn play_extra_life(&self, raudio: &RaylibAudio) {
+        if let Some(extra_life_sound) = &self.extra_life_sound {
+            raudio.play_sound(extra_life_sound);
+        }
+    }
 }
@@ -1,26 +1,118 @@
 use nalgebra::Vector2;
 use raylib::prelude::*;
 
-use self::{meteor::Meteor, player::Player};
+use self::{meteor::Meteor, player::Player, saucer::Saucer};
 
+pub use self::{
+    input::{Action, Input},
+    player::FlightModel,
+    saucer::SaucerKind,
+    weapon::Weapon,
+};
+
+mod collision;
+mod input;
 mod logic;
 mod meteor;
 mod player;
 mod render;
+mod saucer;
+mod weapon;
 
 pub(crate) const SHIP_HEIGHT: f32 = 10f32 / 0.363970f32;
 pub(crate) const PLAYER_SPEED: f32 = 6f32;
 pub(crate) const MAX_BIG_METEORS: usize = 4;
+/// Most big meteors in a wave, however far the player gets.
+pub(crate) const MAX_WAVE_METEORS: usize = 12;
 pub(crate) const METEORS_SPEED: f32 = 2f32;
+/// Pieces a meteor breaks into, unless told otherwise.
+pub const DEFAULT_FRAGMENTS: u8 = 2;
+pub const MAX_FRAGMENTS: u8 = 4;
+/// Pause between two waves, during which the next one is announced.
+pub(crate) const WAVE_DELAY: u32 = 180;
+pub(crate) const WAVE_POINTS: u32 = 500;
 pub(crate) const MAX_SHOTS: usize = 10;
+/// How fast homing shots turn, in radians per tick.
+pub(crate) const HOMING_TURN: f32 = 0.07;
+pub(crate) const PLAYER_LIVES: u32 = 3;
+/// A life is awarded every time the score goes past a multiple of this.
+pub(crate) const EXTRA_LIFE_POINTS: u32 = 10000;
+pub(crate) const MAX_LIVES: u32 = 9;
+/// Minimum time the ship stays away after being destroyed.
+pub(crate) const RESPAWN_DELAY: u32 = 60;
+/// Meteors, saucers and their shots must be this far from the center of the
+/// screen for the ship to respawn.
+pub(crate) const SAFE_ZONE_RADIUS: f32 = 100f32;
+pub(crate) const INVULNERABLE_TICKS: u32 = 180;
+/// Radius of the shield around [`Player::shield_center`], enough for the
+/// whole ship to fit in.
+pub(crate) const SHIELD_RADIUS: f32 = 24f32;
+/// Chance for a hyperspace jump to destroy the ship.
+pub(crate) const HYPERSPACE_FAILURE: f32 = 1.0 / 16.0;
+pub(crate) const HYPERSPACE_COOLDOWN: u32 = 120;
+/// Duration of the hyperspace jump effect.
+pub(crate) const WARP_TICKS: u32 = 20;
+/// Ticks after a kill during which the next one raises the combo.
+pub(crate) const COMBO_TICKS: u32 = 90;
+pub(crate) const MAX_COMBO: u32 = 5;
+
+/// Something that happened during a tick and that the frontend may want to
+/// react to (e.g. by playing a sound).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    Shot {
+        weapon: Weapon,
+    },
+    /// A meteor was destroyed, `variant` picks which explosion sound to play.
+    Explosion {
+        variant: u32,
+    },
+    /// A saucer entered the screen.
+    SaucerSpawned {
+        kind: SaucerKind,
+    },
+    SaucerShot,
+    /// The ship jumped through hyperspace.
+    Hyperspace,
+    /// Something bounced off the shield.
+    ShieldHit,
+    /// The score earned the player another ship.
+    ExtraLife,
+    /// The last ship was destroyed, with the final score.
+    GameOver {
+        score: u32,
+    },
+}
 
 pub struct Game {
+    seed: u64,
+    rng: fastrand::Rng,
+    world_size: (f32, f32),
     game_over: bool,
     pause: bool,
-    victory: bool,
+    /// Current wave, starting at 1.
+    wave: u32,
+    /// Ticks left before the next wave shows up, 0 while one is in play.
+    wave_ticks: u32,
+    score: u32,
+    /// Score multiplier, raised by kills in quick succession.
+    combo: u32,
+    /// Ticks left before the combo drops.
+    combo_ticks: u32,
+    /// Score at which the next extra life is awarded.
+    next_extra_life: u32,
+    /// Ticks left before the ship may respawn.
+    respawn_ticks: u32,
     player: Player,
     meteors: Vec<Meteor>,
+    /// Pieces a meteor breaks into when hit.
+    fragments: u8,
     shots: Vec<Shoot>,
+    saucers: Vec<Saucer>,
+    saucer_shots: Vec<Shoot>,
+    /// Ticks left before the next saucer shows up.
+    saucer_ticks: u32,
+    events: Vec<Event>,
 }
 
 #[derive(Default)]
@@ -31,25 +123,96 @@ pub struct Shoot {
     rotation: f32,
     life: u8,
     color: Color,
+    weapon: Weapon,
+    /// Meteors the shot can still destroy.
+    hits: u8,
+}
+
+impl Shoot {
+    /// Whether the shot left the screen, shots do not wrap around its edges.
+    fn is_off_screen(&self, (width, height): (f32, f32)) -> bool {
+        self.position.x > width + self.radius
+            || self.position.x < -self.radius
+            || self.position.y > height + self.radius
+            || self.position.y < -self.radius
+    }
+}
+
+impl Game {
+    /// Create a game whose meteor layout is fully determined by `seed`.
+    pub fn new(seed: u64, flight_model: FlightModel, fragments: u8) -> Game {
+        let mut game = Game {
+            seed,
+            fragments,
+            ..Game::default()
+        };
+        game.player.flight_model = flight_model;
+        game
+    }
+
+    /// Seed of the current run, to be reported along with bug reports.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn flight_model(&self) -> FlightModel {
+        self.player.flight_model
+    }
+
+    pub fn fragments(&self) -> u8 {
+        self.fragments
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.game_over
+    }
 }
 
 impl Default for Game {
     fn default() -> Game {
+        let seed = fastrand::u64(..);
+        let rng = fastrand::Rng::with_seed(seed);
+        let world_size = (0.0, 0.0);
         let game_over = false;
         let pause = false;
-        let victory = false;
+        let wave = 1;
+        let wave_ticks = 0;
+        let score = 0;
+        let combo = 1;
+        let combo_ticks = 0;
+        let next_extra_life = EXTRA_LIFE_POINTS;
+        let respawn_ticks = 0;
 
         let player = Player::default();
         let meteors = Vec::with_capacity(MAX_BIG_METEORS * 4);
+        let fragments = DEFAULT_FRAGMENTS;
         let shots = Vec::with_capacity(MAX_SHOTS);
+        let saucers = Vec::new();
+        let saucer_shots = Vec::new();
+        let saucer_ticks = 0;
+        let events = Vec::new();
 
         Game {
+            seed,
+            rng,
+            world_size,
             game_over,
             pause,
-            victory,
+            wave,
+            wave_ticks,
+            score,
+            combo,
+            combo_ticks,
+            next_extra_life,
+            respawn_ticks,
             player,
             meteors,
+            fragments,
             shots,
+            saucers,
+            saucer_shots,
+            saucer_ticks,
+            events,
         }
     }
 }
@@ -0,0 +1,117 @@
+use nalgebra::Vector2;
+
+/// Whether the circle of `center` and `radius` overlaps the polygon `poly`,
+/// edges included.
+pub fn polygon_circle(poly: &[Vector2<f32>], center: Vector2<f32>, radius: f32) -> bool {
+    contains_point(poly, center)
+        || edges(poly).any(|(a, b)| segment_distance(a, b, center) <= radius)
+}
+
+/// Whether the polygons `a` and `b` overlap, one inside the other included.
+pub fn polygons(a: &[Vector2<f32>], b: &[Vector2<f32>]) -> bool {
+    contains_point(a, b[0])
+        || contains_point(b, a[0])
+        || edges(a).any(|(a1, a2)| edges(b).any(|(b1, b2)| segments_intersect(a1, a2, b1, b2)))
+}
+
+/// Edges of `poly`, the last one closing it.
+fn edges(poly: &[Vector2<f32>]) -> impl Iterator<Item = (Vector2<f32>, Vector2<f32>)> + '_ {
+    poly.iter()
+        .zip(poly.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+/// Whether `point` is inside `poly`, whatever its winding, concave ones
+/// included.
+fn contains_point(poly: &[Vector2<f32>], point: Vector2<f32>) -> bool {
+    // Count the edges crossed by a ray going right from the point
+    edges(poly)
+        .filter(|(a, b)| {
+            (a.y > point.y) != (b.y > point.y)
+                && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        })
+        .count()
+        % 2
+        == 1
+}
+
+fn segments_intersect(
+    a1: Vector2<f32>,
+    a2: Vector2<f32>,
+    b1: Vector2<f32>,
+    b2: Vector2<f32>,
+) -> bool {
+    let side = |from: Vector2<f32>, to: Vector2<f32>, point: Vector2<f32>| {
+        (to - from).perp(&(point - from))
+    };
+
+    side(a1, a2, b1) * side(a1, a2, b2) <= 0.0 && side(b1, b2, a1) * side(b1, b2, a2) <= 0.0
+}
+
+/// Distance from `point` to the segment going from `a` to `b`.
+fn segment_distance(a: Vector2<f32>, b: Vector2<f32>, point: Vector2<f32>) -> f32 {
+    let ab = b - a;
+    let length_squared = ab.norm_squared();
+
+    let t = if length_squared > 0.0 {
+        ((point - a).dot(&ab) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    (a + ab * t - point).norm()
+}
+
+/// Bring `position` back inside the world, which wraps around its edges.
+pub fn wrap(position: Vector2<f32>, (width, height): (f32, f32)) -> Vector2<f32> {
+    Vector2::new(position.x.rem_euclid(width), position.y.rem_euclid(height))
+}
+
+/// Shortest offset going from `from` to `to` in the wrapping world.
+pub fn wrapped_offset(
+    from: Vector2<f32>,
+    to: Vector2<f32>,
+    (width, height): (f32, f32),
+) -> Vector2<f32> {
+    let offset = to - from;
+
+    Vector2::new(
+        offset.x - width * (offset.x / width).round(),
+        offset.y - height * (offset.y / height).round(),
+    )
+}
+
+/// Whether two circles overlap in the wrapping world.
+pub fn circles(
+    a: Vector2<f32>,
+    radius_a: f32,
+    b: Vector2<f32>,
+    radius_b: f32,
+    world_size: (f32, f32),
+) -> bool {
+    wrapped_offset(a, b, world_size).norm() <= radius_a + radius_b
+}
+
+/// Offsets at which to draw something of `radius` at `position` so that it
+/// shows on every edge it overlaps: no offset, plus one ghost copy per edge
+/// and corner crossed.
+pub fn ghost_offsets(
+    position: Vector2<f32>,
+    radius: f32,
+    (width, height): (f32, f32),
+) -> impl Iterator<Item = Vector2<f32>> {
+    let axis = |p: f32, size: f32| {
+        [
+            Some(0.0),
+            (p - radius < 0.0).then_some(size),
+            (p + radius > size).then_some(-size),
+        ]
+        .into_iter()
+        .flatten()
+    };
+
+    let ys: Vec<f32> = axis(position.y, height).collect();
+
+    axis(position.x, width)
+        .flat_map(move |x| ys.clone().into_iter().map(move |y| Vector2::new(x, y)))
+}
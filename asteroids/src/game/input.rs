@@ -0,0 +1,190 @@
+use common::input::{self, Actions, Binding};
+use raylib::ffi::{GamepadAxis, GamepadButton, KeyboardKey};
+
+/// What the player can do, as named in bindings files.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    RotateLeft,
+    RotateRight,
+    Thrust,
+    Brake,
+    Fire,
+    Pause,
+    Restart,
+    SwitchWeapon,
+    Hyperspace,
+    Shield,
+}
+
+impl input::Action for Action {
+    const ALL: &'static [Self] = &[
+        Action::RotateLeft,
+        Action::RotateRight,
+        Action::Thrust,
+        Action::Brake,
+        Action::Fire,
+        Action::Pause,
+        Action::Restart,
+        Action::SwitchWeapon,
+        Action::Hyperspace,
+        Action::Shield,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Action::RotateLeft => "rotate_left",
+            Action::RotateRight => "rotate_right",
+            Action::Thrust => "thrust",
+            Action::Brake => "brake",
+            Action::Fire => "fire",
+            Action::Pause => "pause",
+            Action::Restart => "restart",
+            Action::SwitchWeapon => "switch_weapon",
+            Action::Hyperspace => "hyperspace",
+            Action::Shield => "shield",
+        }
+    }
+
+    fn default_bindings(self) -> &'static [Binding] {
+        use Binding::*;
+
+        match self {
+            Action::RotateLeft => &[
+                Key(KeyboardKey::KEY_LEFT),
+                Button(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT),
+                Axis {
+                    axis: GamepadAxis::GAMEPAD_AXIS_LEFT_X,
+                    positive: false,
+                },
+            ],
+            Action::RotateRight => &[
+                Key(KeyboardKey::KEY_RIGHT),
+                Button(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT),
+                Axis {
+                    axis: GamepadAxis::GAMEPAD_AXIS_LEFT_X,
+                    positive: true,
+                },
+            ],
+            Action::Thrust => &[
+                Key(KeyboardKey::KEY_UP),
+                Button(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP),
+                Axis {
+                    axis: GamepadAxis::GAMEPAD_AXIS_LEFT_Y,
+                    positive: false,
+                },
+            ],
+            Action::Brake => &[
+                Key(KeyboardKey::KEY_DOWN),
+                Button(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN),
+                Axis {
+                    axis: GamepadAxis::GAMEPAD_AXIS_LEFT_Y,
+                    positive: true,
+                },
+            ],
+            Action::Fire => &[
+                Key(KeyboardKey::KEY_SPACE),
+                Button(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN),
+            ],
+            // Pausing and restarting never happen at the same time
+            Action::Pause => &[
+                Key(KeyboardKey::KEY_P),
+                Button(GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT),
+            ],
+            Action::Restart => &[
+                Key(KeyboardKey::KEY_ENTER),
+                Button(GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT),
+            ],
+            Action::SwitchWeapon => &[
+                Key(KeyboardKey::KEY_TAB),
+                Button(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP),
+            ],
+            Action::Hyperspace => &[
+                Key(KeyboardKey::KEY_H),
+                Button(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT),
+            ],
+            Action::Shield => &[
+                Key(KeyboardKey::KEY_S),
+                Button(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT),
+            ],
+        }
+    }
+}
+
+/// Player inputs for a single game tick.
+///
+/// The game only ever reads inputs through this struct, so it can be stepped
+/// without a window by building frames by hand.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct Input {
+    pub rotate_left: bool,
+    pub rotate_right: bool,
+    pub thrust: bool,
+    pub brake: bool,
+    /// Fire was pressed this tick.
+    pub fire_pressed: bool,
+    /// Fire is held down, automatic weapons keep firing.
+    pub fire_held: bool,
+    pub pause: bool,
+    pub restart: bool,
+    pub switch_weapon: bool,
+    pub hyperspace: bool,
+    pub shield: bool,
+}
+
+impl Input {
+    /// Read the actions of the current frame.
+    pub fn poll(actions: &Actions<Action>) -> Self {
+        Self {
+            rotate_left: actions.is_down(Action::RotateLeft),
+            rotate_right: actions.is_down(Action::RotateRight),
+            thrust: actions.is_down(Action::Thrust),
+            brake: actions.is_down(Action::Brake),
+            fire_pressed: actions.is_pressed(Action::Fire),
+            fire_held: actions.is_down(Action::Fire),
+            pause: actions.is_pressed(Action::Pause),
+            restart: actions.is_pressed(Action::Restart),
+            switch_weapon: actions.is_pressed(Action::SwitchWeapon),
+            hyperspace: actions.is_pressed(Action::Hyperspace),
+            shield: actions.is_down(Action::Shield),
+        }
+    }
+
+    /// Pack the inputs in a bit set, one bit per field in declaration order.
+    pub fn to_bits(self) -> u16 {
+        [
+            self.rotate_left,
+            self.rotate_right,
+            self.thrust,
+            self.brake,
+            self.fire_pressed,
+            self.fire_held,
+            self.pause,
+            self.restart,
+            self.switch_weapon,
+            self.hyperspace,
+            self.shield,
+        ]
+        .iter()
+        .enumerate()
+        .fold(0, |bits, (i, &pressed)| bits | (pressed as u16) << i)
+    }
+
+    /// Inverse of [`Input::to_bits`].
+    pub fn from_bits(bits: u16) -> Self {
+        let bit = |i: u16| bits & (1 << i) != 0;
+
+        Self {
+            rotate_left: bit(0),
+            rotate_right: bit(1),
+            thrust: bit(2),
+            brake: bit(3),
+            fire_pressed: bit(4),
+            fire_held: bit(5),
+            pause: bit(6),
+            restart: bit(7),
+            switch_weapon: bit(8),
+            hyperspace: bit(9),
+            shield: bit(10),
+        }
+    }
+}
@@ -1,33 +1,59 @@
-use nalgebra::{Vector2, Vector3};
-use raylib::{core::collision::check_collision_circles, ffi::KeyboardKey, prelude::*};
+use std::f32::consts::{PI, TAU};
 
-use super::{meteor::MeteorKind, *};
-use crate::assets::Assets;
+use nalgebra::{Rotation2, Vector2};
+use raylib::prelude::*;
 
-impl Game {
-    pub fn init(&mut self, rl: &RaylibHandle) {
-        let (width, height) = (rl.get_screen_width() as f32, rl.get_screen_height() as f32);
-        let half_width = width / 2.0;
-        let half_height = height / 2.0;
+use super::{collision, meteor::MeteorKind, *};
 
-        self.player.position = Vector2::new(half_width, half_height - (SHIP_HEIGHT / 2f32));
-        self.player.acceleration = 0f32;
-        self.player.collider = Vector3::new(
-            self.player.position.x + self.player.rotation.to_radians().sin() * (SHIP_HEIGHT / 2.5),
-            self.player.position.y - self.player.rotation.to_radians().cos() * (SHIP_HEIGHT / 2.5),
-            12f32,
-        );
+impl Game {
+    /// Reset the game for a world of `world_size` (width, height).
+    pub fn init(&mut self, world_size: (f32, f32)) {
+        self.world_size = world_size;
+        self.rng.seed(self.seed);
+        self.score = 0;
+        self.combo = 1;
+        self.combo_ticks = 0;
+        self.next_extra_life = EXTRA_LIFE_POINTS;
+        self.respawn_ticks = 0;
+
+        self.player.lives = PLAYER_LIVES;
+        self.player.shield_energy = 1f32;
+        self.player.shield_locked = false;
         self.player.color = Color::MAROON;
+        self.respawn();
+
+        self.wave = 1;
+        self.wave_ticks = 0;
+        self.spawn_wave();
+        self.shots.clear();
+        self.reset_saucers();
+    }
+
+    /// Fill the field with the big meteors of the current wave, each wave has
+    /// more of them and they get faster.
+    fn spawn_wave(&mut self) {
+        let (width, height) = self.world_size;
+        let (player_x, player_y) = (self.player.position.x as i32, self.player.position.y as i32);
+
+        let count = (MAX_BIG_METEORS + self.wave as usize - 1).min(MAX_WAVE_METEORS);
+        let speed_factor = (1.0 + (self.wave - 1) as f32 * 0.15).min(2.0);
+
+        // Distance along an axis of the wrapping world
+        let distance = |a: i32, b: i32, size: f32| {
+            let distance = (a - b).rem_euclid(size as i32);
+            distance.min(size as i32 - distance)
+        };
 
         let mut correct_range = false;
 
         self.meteors.clear();
-        for _ in 0..MAX_BIG_METEORS {
-            let mut x: i32 = rl.get_random_value(0, width as i32);
+        for _ in 0..count {
+            let mut x: i32 = self.rng.i32(0..=width as i32);
 
+            // Keep clear of the player
             while !correct_range {
-                if x > half_width as i32 - 150 && x < half_width as i32 + 150 {
-                    x = rl.get_random_value(0, width as i32);
+                if distance(x, player_x, width) < 150 {
+                    x = self.rng.i32(0..=width as i32);
                 } else {
                     correct_range = true;
                 }
@@ -35,11 +61,11 @@ impl Game {
 
             correct_range = false;
 
-            let mut y: i32 = rl.get_random_value(0, height as i32);
+            let mut y: i32 = self.rng.i32(0..=height as i32);
 
             while !correct_range {
-                if y > half_height as i32 - 150 && y < half_height as i32 + 150 {
-                    y = rl.get_random_value(0, height as i32);
+                if distance(y, player_y, height) < 150 {
+                    y = self.rng.i32(0..=height as i32);
                 } else {
                     correct_range = true;
                 }
@@ -47,150 +73,658 @@ impl Game {
 
             correct_range = false;
 
-            let mut vel_x: i32 = rl.get_random_value(-METEORS_SPEED as i32, METEORS_SPEED as i32);
-            let mut vel_y: i32 = rl.get_random_value(-METEORS_SPEED as i32, METEORS_SPEED as i32);
+            let mut vel_x: i32 = self.rng.i32(-METEORS_SPEED as i32..=METEORS_SPEED as i32);
+            let mut vel_y: i32 = self.rng.i32(-METEORS_SPEED as i32..=METEORS_SPEED as i32);
 
             while !correct_range {
                 if vel_x == 0 && vel_y == 0 {
-                    vel_x = rl.get_random_value(-METEORS_SPEED as i32, METEORS_SPEED as i32);
-                    vel_y = rl.get_random_value(-METEORS_SPEED as i32, METEORS_SPEED as i32);
+                    vel_x = self.rng.i32(-METEORS_SPEED as i32..=METEORS_SPEED as i32);
+                    vel_y = self.rng.i32(-METEORS_SPEED as i32..=METEORS_SPEED as i32);
                 } else {
                     correct_range = true;
                 }
             }
 
-            self.meteors.push(Meteor {
-                position: Vector2::new(x as f32, y as f32),
-                speed: Vector2::new(vel_x as f32, vel_y as f32),
-                radius: MeteorKind::Big.get_radius(),
-                active: true,
-                color: Color::BLUE,
-                kind: MeteorKind::Big,
-            });
+            let meteor = Meteor::new(
+                MeteorKind::Big,
+                Vector2::new(x as f32, y as f32),
+                Vector2::new(vel_x as f32, vel_y as f32) * speed_factor,
+                &mut self.rng,
+            );
+            self.meteors.push(meteor);
         }
     }
 
-    pub fn update(&mut self, rl: &RaylibHandle, assets: &Assets, raudio: &RaylibAudio) {
+    /// Advance the game by one tick.
+    pub fn update(&mut self, input: &Input) {
         if !self.game_over {
-            self.game_iteration(rl, assets, raudio);
-        } else if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
-            self.init(rl);
+            self.game_iteration(input);
+        } else if input.restart {
+            // Every run gets its own seed so that it can be replayed on its own.
+            self.seed = self.rng.u64(..);
+            self.init(self.world_size);
             self.game_over = false;
         }
     }
 
-    fn game_iteration(&mut self, rl: &RaylibHandle, assets: &Assets, raudio: &RaylibAudio) {
-        let (width, height) = (rl.get_screen_width() as f32, rl.get_screen_height() as f32);
+    /// Put the ship back at the center of the screen, briefly invulnerable.
+    fn respawn(&mut self) {
+        let (width, height) = self.world_size;
 
-        if rl.is_key_pressed(KeyboardKey::KEY_P) {
+        self.player.position = Vector2::new(width / 2.0, height / 2.0 - (SHIP_HEIGHT / 2f32));
+        self.player.rotation = 0f32;
+        self.player.speed = Vector2::zeros();
+        self.player.acceleration = 0f32;
+        self.player.cooldown = 0;
+        self.player.shielded = false;
+        self.player.hyperspace_cooldown = 0;
+        self.player.warp_ticks = 0;
+        self.player.alive = true;
+        self.player.invulnerable = INVULNERABLE_TICKS;
+    }
+
+    /// Whether no meteor, saucer or saucer shot is close enough to the center
+    /// of the screen to hit a ship respawning there.
+    fn is_safe_to_respawn(&self) -> bool {
+        let (width, height) = self.world_size;
+        let center = Vector2::new(width / 2.0, height / 2.0);
+        let is_clear = |position, radius| {
+            collision::wrapped_offset(center, position, self.world_size).norm()
+                > SAFE_ZONE_RADIUS + radius
+        };
+
+        self.meteors
+            .iter()
+            .all(|meteor| !meteor.active || is_clear(meteor.position, meteor.radius))
+            && self
+                .saucers
+                .iter()
+                .all(|saucer| is_clear(saucer.position, saucer.kind.radius()))
+            && self
+                .saucer_shots
+                .iter()
+                .all(|shot| is_clear(shot.position, shot.radius))
+    }
+
+    /// Take the events produced since the last call.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn game_iteration(&mut self, input: &Input) {
+        let (width, height) = self.world_size;
+
+        if input.pause {
             self.pause = !self.pause;
         }
 
         if !self.pause {
-            self.player.process_player_movement(rl, (width, height));
+            // Between waves
+            if self.wave_ticks > 0 {
+                self.wave_ticks -= 1;
 
-            if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
-                self.make_shot(assets, raudio);
+                if self.wave_ticks == 0 {
+                    self.spawn_wave();
+                }
             }
 
-            self.process_shots(assets, raudio, (width, height));
-            self.check_meteor_player_collisions();
+            if self.player.alive {
+                self.player.invulnerable = self.player.invulnerable.saturating_sub(1);
+                self.player.cooldown = self.player.cooldown.saturating_sub(1);
+                self.player.hyperspace_cooldown = self.player.hyperspace_cooldown.saturating_sub(1);
+                self.player.warp_ticks = self.player.warp_ticks.saturating_sub(1);
+                self.player.process_player_movement(input, (width, height));
+                self.player.process_player_shield(input);
+
+                if input.hyperspace && self.player.hyperspace_cooldown == 0 {
+                    self.hyperspace();
+                }
+
+                if input.switch_weapon {
+                    self.player.weapon = self.player.weapon.next();
+                }
+
+                if input.fire_pressed || input.fire_held && self.player.weapon.automatic() {
+                    self.make_shot();
+                }
+            } else {
+                // Wait for the center of the screen to be clear
+                self.respawn_ticks = self.respawn_ticks.saturating_sub(1);
+
+                if self.respawn_ticks == 0 && self.is_safe_to_respawn() {
+                    self.respawn();
+                }
+            }
+
+            self.combo_ticks = self.combo_ticks.saturating_sub(1);
+            if self.combo_ticks == 0 {
+                self.combo = 1;
+            }
+
+            self.process_shots((width, height));
+
+            if self.player.alive {
+                self.check_meteor_player_collisions();
+            }
+
+            self.update_saucers();
+            self.check_saucer_collisions();
 
             self.meteors
                 .iter_mut()
                 .for_each(|meteor| meteor.update((width, height)));
         }
 
-        if self.meteors.iter().all(|meteor| !meteor.active) {
-            self.victory = true;
+        // Field cleared, announce the next wave
+        if self.wave_ticks == 0 && self.meteors.iter().all(|meteor| !meteor.active) {
+            self.score += WAVE_POINTS * self.wave;
+            self.wave += 1;
+            self.wave_ticks = WAVE_DELAY;
+        }
+
+        self.award_extra_lives();
+    }
+
+    fn award_extra_lives(&mut self) {
+        while self.score >= self.next_extra_life {
+            self.next_extra_life += EXTRA_LIFE_POINTS;
+
+            if self.player.lives < MAX_LIVES {
+                self.player.lives += 1;
+                self.events.push(Event::ExtraLife);
+            }
         }
     }
 
     fn check_meteor_player_collisions(&mut self) {
-        self.player.collider = Vector3::new(
-            self.player.position.x + self.player.rotation.to_radians().sin() * (SHIP_HEIGHT / 2.5),
-            self.player.position.y - self.player.rotation.to_radians().cos() * (SHIP_HEIGHT / 2.5),
-            12f32,
-        );
+        if self.player.shielded {
+            self.deflect_meteors();
+            return;
+        }
+
+        if self.player.invulnerable > 0 {
+            return;
+        }
 
         if self.meteors.iter().any(|meteor| {
             meteor.active
-                && check_collision_circles(
-                    Vector2::new(self.player.collider.x, self.player.collider.y).into(),
-                    self.player.collider.z,
-                    meteor.position.into(),
-                    meteor.radius,
+                && meteor.collides_with_polygon(
+                    &self.player.vertices(),
+                    self.player.position,
+                    self.world_size,
                 )
         }) {
+            self.destroy_ship();
+        }
+    }
+
+    /// Meteors touching the shield bounce off it.
+    fn deflect_meteors(&mut self) {
+        for meteor in self.meteors.iter_mut().filter(|meteor| meteor.active) {
+            let offset = collision::wrapped_offset(
+                self.player.shield_center(),
+                meteor.position,
+                self.world_size,
+            );
+            let distance = offset.norm();
+            let min_distance = SHIELD_RADIUS + meteor.radius;
+
+            if distance >= min_distance || distance == 0.0 {
+                continue;
+            }
+
+            let normal = offset / distance;
+
+            // Only turn back the meteors heading towards the ship
+            let towards = meteor.speed.dot(&normal);
+            if towards < 0.0 {
+                meteor.speed -= 2.0 * towards * normal;
+                self.events.push(Event::ShieldHit);
+            }
+
+            meteor.position = collision::wrap(
+                self.player.position + normal * min_distance,
+                self.world_size,
+            );
+        }
+    }
+
+    /// Jump to a random spot of the screen, with a small chance of not making it.
+    fn hyperspace(&mut self) {
+        let (width, height) = self.world_size;
+
+        self.events.push(Event::Hyperspace);
+        self.player.hyperspace_cooldown = HYPERSPACE_COOLDOWN;
+
+        if self.rng.f32() < HYPERSPACE_FAILURE {
+            self.destroy_ship();
+            return;
+        }
+
+        self.player.warp_from = self.player.position;
+        self.player.warp_ticks = WARP_TICKS;
+        self.player.position = Vector2::new(self.rng.f32() * width, self.rng.f32() * height);
+        self.player.speed = Vector2::zeros();
+        self.player.acceleration = 0f32;
+    }
+
+    /// Take a life, the ship respawns after a while if any is left.
+    pub(crate) fn destroy_ship(&mut self) {
+        self.events.push(Event::Explosion {
+            variant: self.rng.u32(..),
+        });
+
+        self.player.alive = false;
+        self.player.lives -= 1;
+
+        if self.player.lives == 0 {
             self.game_over = true;
+            self.events.push(Event::GameOver { score: self.score });
+        } else {
+            self.respawn_ticks = RESPAWN_DELAY;
         }
     }
 
-    fn process_shots(
-        &mut self,
-        assets: &Assets,
-        raudio: &RaylibAudio,
-        (width, height): (f32, f32),
-    ) {
+    /// Destroy the meteor at `index` without scoring, it splits away from
+    /// `rotation` like after a shot.
+    pub(crate) fn break_meteor(&mut self, index: usize, rotation: f32) {
+        self.events.push(Event::Explosion {
+            variant: self.rng.u32(..),
+        });
+
+        let meteor = &mut self.meteors[index];
+        meteor.active = false;
+
+        let fragments = meteor.split(rotation, self.fragments, &mut self.rng);
+        self.meteors.extend(fragments);
+    }
+
+    fn process_shots(&mut self, world_size: (f32, f32)) {
         self.shots.retain_mut(|shot| {
             shot.life -= 1;
 
+            if shot.weapon == Weapon::Homing {
+                let target = self
+                    .meteors
+                    .iter()
+                    .filter(|meteor| meteor.active)
+                    .map(|meteor| meteor.position - shot.position)
+                    .min_by(|a, b| a.norm_squared().total_cmp(&b.norm_squared()));
+
+                // Turn towards the target, shots move with y pointing up.
+                if let Some(offset) = target {
+                    let to_target = Vector2::new(offset.x, -offset.y);
+                    let turn = to_target.y.atan2(to_target.x) - shot.speed.y.atan2(shot.speed.x);
+                    let turn = (turn + PI).rem_euclid(TAU) - PI;
+
+                    shot.speed = Rotation2::new(turn.clamp(-HOMING_TURN, HOMING_TURN)) * shot.speed;
+                }
+            }
+
             shot.position.x += shot.speed.x;
             shot.position.y -= shot.speed.y;
 
-            if (shot.position.x > width + shot.radius)
-                || (shot.position.x < -shot.radius)
-                || (shot.position.y > height + shot.radius)
-                || (shot.position.y < -shot.radius)
-                || (shot.life == 0)
-            {
+            if shot.is_off_screen(world_size) || shot.life == 0 {
                 return false;
             }
 
             if let Some(hit) = self.meteors.iter_mut().find(|meteor| {
-                meteor.active
-                    && check_collision_circles(
-                        shot.position.into(),
-                        shot.radius,
-                        meteor.position.into(),
-                        meteor.radius,
-                    )
+                meteor.active && meteor.collides_with(shot.position, shot.radius, world_size)
             }) {
-                assets.play_explosion(raudio);
+                self.events.push(Event::Explosion {
+                    variant: self.rng.u32(..),
+                });
 
                 hit.active = false;
 
-                if let Some(splited) = hit.split(shot) {
-                    self.meteors.extend(splited.iter());
+                // Kills in quick succession are worth more.
+                if self.combo_ticks > 0 {
+                    self.combo = (self.combo + 1).min(MAX_COMBO);
                 }
+                self.combo_ticks = COMBO_TICKS;
+                self.score += hit.kind.points() * self.combo;
 
-                return false;
+                let fragments = hit.split(shot.rotation, self.fragments, &mut self.rng);
+                self.meteors.extend(fragments);
+
+                // Piercing shots keep going through a few meteors.
+                shot.hits -= 1;
+                return shot.hits > 0;
+            }
+
+            if let Some(index) = self.saucers.iter().position(|saucer| {
+                collision::circles(
+                    shot.position,
+                    shot.radius,
+                    saucer.position,
+                    saucer.kind.radius(),
+                    world_size,
+                )
+            }) {
+                self.events.push(Event::Explosion {
+                    variant: self.rng.u32(..),
+                });
+
+                let saucer = self.saucers.remove(index);
+                self.score += saucer.kind.points() * self.combo;
+
+                shot.hits -= 1;
+                return shot.hits > 0;
             }
 
             true
         });
     }
 
-    fn make_shot(&mut self, assets: &Assets, raudio: &RaylibAudio) {
-        if let Some(ref shoot_sound) = assets.shoot_sound {
-            raudio.play_sound(shoot_sound);
-        }
-
-        self.shots.push(Shoot {
-            position: self.player.position
-                + Vector2::new(
-                    self.player.rotation.to_radians().sin() * SHIP_HEIGHT,
-                    -self.player.rotation.to_radians().cos() * SHIP_HEIGHT,
-                ),
-            speed: 1.5
-                * Vector2::new(
-                    self.player.rotation.to_radians().sin() * PLAYER_SPEED,
-                    self.player.rotation.to_radians().cos() * PLAYER_SPEED,
-                ),
-            rotation: self.player.rotation,
-            radius: 2f32,
+    fn make_shot(&mut self) {
+        let weapon = self.player.weapon;
+
+        if self.player.cooldown > 0 || self.shots.len() >= MAX_SHOTS {
+            return;
+        }
+
+        self.player.cooldown = weapon.cooldown();
+        self.events.push(Event::Shot { weapon });
+
+        for angle in weapon.angles() {
+            if self.shots.len() >= MAX_SHOTS {
+                break;
+            }
+
+            let rotation = self.player.rotation + angle;
+
+            self.shots.push(Shoot {
+                position: self.player.position
+                    + Vector2::new(
+                        self.player.rotation.to_radians().sin() * SHIP_HEIGHT,
+                        -self.player.rotation.to_radians().cos() * SHIP_HEIGHT,
+                    ),
+                speed: weapon.speed()
+                    * Vector2::new(
+                        rotation.to_radians().sin() * PLAYER_SPEED,
+                        rotation.to_radians().cos() * PLAYER_SPEED,
+                    ),
+                rotation,
+                radius: weapon.radius(),
+                life: weapon.life(),
+                color: weapon.color(),
+                weapon,
+                hits: weapon.hits(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+    use crate::replay::Replay;
+
+    const WORLD_SIZE: (f32, f32) = (800.0, 480.0);
+
+    fn new_game(seed: u64) -> Game {
+        let mut game = Game::new(seed, FlightModel::Newtonian, DEFAULT_FRAGMENTS);
+        game.init(WORLD_SIZE);
+        game
+    }
+
+    /// Inputs of a player mashing keys, changing every few ticks.
+    fn inputs(seed: u64, ticks: usize) -> Vec<Input> {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        let mut input = Input::default();
+
+        (0..ticks)
+            .map(|tick| {
+                if tick % 20 == 0 {
+                    input = Input {
+                        rotate_left: rng.bool(),
+                        rotate_right: rng.bool(),
+                        thrust: rng.u8(..4) == 0,
+                        brake: rng.bool(),
+                        fire_held: true,
+                        restart: true,
+                        ..Input::default()
+                    };
+                }
+                input.fire_pressed = tick % 15 == 0;
+                input
+            })
+            .collect()
+    }
+
+    /// What a run looks like, to tell whether two runs went the same way.
+    fn state(game: &Game) -> (u64, u32, u32, u32, Vector2<f32>, Vec<Vector2<f32>>) {
+        (
+            game.seed,
+            game.score,
+            game.wave,
+            game.player.lives,
+            game.player.position,
+            game.meteors.iter().map(|meteor| meteor.position).collect(),
+        )
+    }
+
+    #[test]
+    fn thousands_of_ticks_without_a_window() {
+        let mut game = new_game(42);
+        let mut shots = 0;
+        let mut best_score = 0;
+
+        for input in inputs(7, 10_000) {
+            game.update(&input);
+
+            shots += game
+                .take_events()
+                .iter()
+                .filter(|event| matches!(event, Event::Shot { .. }))
+                .count();
+            best_score = best_score.max(game.score);
+
+            let (width, height) = WORLD_SIZE;
+            assert!((0.0..=width).contains(&game.player.position.x));
+            assert!((0.0..=height).contains(&game.player.position.y));
+            assert!(game.shots.len() <= MAX_SHOTS);
+            assert!(game.meteors.iter().all(|meteor| {
+                (0.0..=width).contains(&meteor.position.x)
+                    && (0.0..=height).contains(&meteor.position.y)
+            }));
+        }
+
+        assert!(shots > 0);
+        assert!(best_score > 0);
+    }
+
+    #[test]
+    fn only_automatic_weapons_fire_while_held() {
+        for weapon in Weapon::ALL {
+            let mut game = new_game(11);
+            game.player.weapon = weapon;
+            game.take_events();
+
+            let press = Input {
+                fire_pressed: true,
+                fire_held: true,
+                ..Input::default()
+            };
+            let hold = Input {
+                fire_held: true,
+                ..Input::default()
+            };
+
+            game.update(&press);
+            for _ in 0..60 {
+                game.update(&hold);
+            }
+
+            let shots = game
+                .take_events()
+                .iter()
+                .filter(|event| matches!(event, Event::Shot { .. }))
+                .count();
+
+            if weapon.automatic() {
+                assert!(shots > 10, "{weapon:?} fired {shots} times");
+            } else {
+                assert_eq!(shots, 1, "{weapon:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn saucer_hitting_a_meteor_explodes_once() {
+        let mut game = new_game(8);
+        let meteor = game.meteors[0].position;
+
+        game.saucers.push(Saucer {
+            kind: SaucerKind::Small,
+            position: meteor,
+            speed: Vector2::new(SaucerKind::Small.speed(), 0.0),
+            turn_ticks: 100,
+            fire_ticks: 100,
+            travel: 0.0,
+        });
+        game.take_events();
+        game.check_saucer_collisions();
+
+        let explosions = game
+            .take_events()
+            .iter()
+            .filter(|event| matches!(event, Event::Explosion { .. }))
+            .count();
+
+        assert!(game.saucers.is_empty());
+        assert_eq!(explosions, 1);
+    }
+
+    #[test]
+    fn shots_leaving_the_screen_are_removed() {
+        let mut game = new_game(3);
+        game.meteors.clear();
+        game.shots.push(Shoot {
+            position: Vector2::new(WORLD_SIZE.0 - 4.0, 100.0),
+            speed: Vector2::new(5.0, 0.0),
+            radius: 2.0,
+            life: 60,
+            hits: 1,
+            ..Shoot::default()
+        });
+
+        game.process_shots(WORLD_SIZE);
+        assert_eq!(game.shots.len(), 1);
+
+        game.process_shots(WORLD_SIZE);
+        assert!(game.shots.is_empty());
+    }
+
+    #[test]
+    fn respawn_waits_for_saucers_and_their_shots() {
+        let mut game = new_game(5);
+        let center = Vector2::new(WORLD_SIZE.0 / 2.0, WORLD_SIZE.1 / 2.0);
+
+        game.meteors.clear();
+        game.saucers.push(Saucer {
+            kind: SaucerKind::Large,
+            position: center,
+            speed: Vector2::zeros(),
+            turn_ticks: 1,
+            fire_ticks: 1,
+            travel: 0.0,
+        });
+        assert!(!game.is_safe_to_respawn());
+
+        game.saucers.clear();
+        game.saucer_shots.push(Shoot {
+            position: center + Vector2::new(SAFE_ZONE_RADIUS, 0.0),
+            radius: 2.0,
+            ..Shoot::default()
+        });
+        assert!(!game.is_safe_to_respawn());
+
+        game.saucer_shots.clear();
+        assert!(game.is_safe_to_respawn());
+    }
+
+    #[test]
+    fn shield_covers_the_nose_of_the_ship() {
+        let mut game = new_game(5);
+        game.meteors.clear();
+        game.player.rotation = 0.0;
+        game.player.invulnerable = 0;
+        game.player.shielded = true;
+
+        // Just off the nose, further than the shield radius from the base
+        game.saucer_shots.push(Shoot {
+            position: game.player.position - Vector2::new(0.0, SHIP_HEIGHT - 1.0),
+            radius: 2.0,
             life: 60,
-            color: Color::BLACK,
-        })
+            ..Shoot::default()
+        });
+        game.check_saucer_collisions();
+
+        assert!(game.player.alive);
+        assert!(game.saucer_shots.is_empty());
+        assert_eq!(game.take_events(), [Event::ShieldHit]);
+    }
+
+    #[test]
+    fn same_seed_and_inputs_same_run() {
+        let mut first = new_game(1234);
+        let mut second = new_game(1234);
+
+        for input in inputs(99, 5_000) {
+            first.update(&input);
+            second.update(&input);
+        }
+
+        assert_eq!(state(&first), state(&second));
+    }
+
+    #[test]
+    fn replay_reproduces_the_run() {
+        let mut game = new_game(2024);
+        let mut recording = Replay::new(
+            game.seed(),
+            game.flight_model(),
+            game.fragments(),
+            WORLD_SIZE,
+        );
+
+        for input in inputs(3, 5_000) {
+            recording.push(input);
+            game.update(&input);
+        }
+
+        let mut file = Vec::new();
+        recording.write_to(&mut file).unwrap();
+        let replay = Replay::read_from(file.as_slice()).unwrap();
+
+        let mut replayed = Game::new(replay.seed, replay.flight_model, replay.fragments);
+        replayed.init(replay.world_size);
+        for input in replay.inputs() {
+            replayed.update(&input);
+        }
+
+        assert_eq!(state(&replayed), state(&game));
+    }
+
+    #[test]
+    fn replays_with_a_bad_world_size_are_rejected() {
+        for world_size in [
+            (0.0, 480.0),
+            (800.0, f32::NAN),
+            (f32::INFINITY, 480.0),
+            (200.0, 480.0),
+            (800.0, -480.0),
+        ] {
+            let mut file = Vec::new();
+            Replay::new(1, FlightModel::Newtonian, DEFAULT_FRAGMENTS, world_size)
+                .write_to(&mut file)
+                .unwrap();
+
+            let err = Replay::read_from(file.as_slice()).err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{world_size:?}");
+        }
     }
 }
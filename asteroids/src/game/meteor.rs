@@ -1,7 +1,18 @@
-use nalgebra::Vector2;
+use std::{f32::consts::TAU, ops::RangeInclusive};
+
+use nalgebra::{Rotation2, Vector2};
 use raylib::prelude::*;
 
-use super::{Shoot, METEORS_SPEED};
+use super::{collision, METEORS_SPEED};
+
+/// How far fragments fly along the impact direction, relative to across it.
+const IMPACT_SPREAD: f32 = 0.3;
+/// Random turn of the fragments pattern, in radians either way.
+const SPREAD_JITTER: f32 = 0.4;
+/// Corners of a meteor outline.
+const OUTLINE_VERTICES: usize = 11;
+/// How deep the outline dents may go, relative to the radius.
+const OUTLINE_DENT: f32 = 0.35;
 
 #[derive(Copy, Clone, Default)]
 pub enum MeteorKind {
@@ -27,52 +38,160 @@ impl MeteorKind {
             MeteorKind::Small => 10.0,
         }
     }
+
+    /// Speeds at which fragments of this kind fly away from where their
+    /// parent was heading.
+    pub fn spread_speed(&self) -> RangeInclusive<f32> {
+        match self {
+            MeteorKind::Big => METEORS_SPEED * 0.5..=METEORS_SPEED,
+            MeteorKind::Medium => METEORS_SPEED * 0.75..=METEORS_SPEED * 1.25,
+            MeteorKind::Small => METEORS_SPEED..=METEORS_SPEED * 1.75,
+        }
+    }
+
+    /// Fastest a meteor of this kind spins, in radians per tick.
+    pub fn max_spin(&self) -> f32 {
+        match self {
+            MeteorKind::Big => 0.015,
+            MeteorKind::Medium => 0.03,
+            MeteorKind::Small => 0.05,
+        }
+    }
+
+    /// Points for shooting down a meteor of this kind, the smaller the better.
+    pub fn points(&self) -> u32 {
+        match self {
+            MeteorKind::Big => 20,
+            MeteorKind::Medium => 50,
+            MeteorKind::Small => 100,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Default)]
 pub struct Meteor {
     pub position: Vector2<f32>,
     pub speed: Vector2<f32>,
+    /// Distance from the center to the farthest corner of the outline.
     pub radius: f32,
     pub active: bool,
     pub kind: MeteorKind,
     pub color: Color,
+    /// Corners of the jagged outline around the center, before rotation.
+    pub outline: [Vector2<f32>; OUTLINE_VERTICES],
+    /// Current angle, in radians.
+    pub rotation: f32,
+    /// Angular velocity, in radians per tick.
+    pub spin: f32,
 }
 
 impl Meteor {
-    pub fn update(&mut self, (width, height): (f32, f32)) {
+    /// A meteor of `kind` with a random outline and spin.
+    pub fn new(
+        kind: MeteorKind,
+        position: Vector2<f32>,
+        speed: Vector2<f32>,
+        rng: &mut fastrand::Rng,
+    ) -> Self {
+        let radius = kind.get_radius();
+
+        // Corners go around the center at irregular angles and depths, one of
+        // them always reaching the full radius.
+        let full = rng.usize(..OUTLINE_VERTICES);
+        let outline = std::array::from_fn(|i| {
+            let angle = (i as f32 + rng.f32() * 0.6 - 0.3) * TAU / OUTLINE_VERTICES as f32;
+            let depth = if i == full {
+                1.0
+            } else {
+                1.0 - rng.f32() * OUTLINE_DENT
+            };
+
+            Vector2::new(angle.cos(), angle.sin()) * radius * depth
+        });
+
+        Self {
+            position,
+            speed,
+            radius,
+            active: true,
+            kind,
+            color: Color::BLUE,
+            outline,
+            rotation: rng.f32() * TAU,
+            spin: (rng.f32() * 2.0 - 1.0) * kind.max_spin(),
+        }
+    }
+
+    pub fn update(&mut self, world_size: (f32, f32)) {
         if self.active {
-            self.position += self.speed;
-
-            if self.position.x > width + self.radius {
-                self.position.x = -self.radius;
-            } else if self.position.x < -self.radius {
-                self.position.x = width + self.radius;
-            }
-
-            if self.position.y > height + self.radius {
-                self.position.y = -self.radius;
-            } else if self.position.y < -self.radius {
-                self.position.y = height + self.radius;
-            }
+            self.position = collision::wrap(self.position + self.speed, world_size);
+            self.rotation = (self.rotation + self.spin).rem_euclid(TAU);
         }
     }
 
-    /// Split the meteor in two smaller parts, if possible.
-    pub fn split(&self, shot: &Shoot) -> Option<[Self; 2]> {
-        self.kind.next().map(|kind| {
-            [-1.0, 1.0].map(|dir| Self {
-                position: Vector2::new(self.position.x, self.position.y),
-                speed: dir
-                    * Vector2::new(
-                        shot.rotation.to_radians().cos() * METEORS_SPEED * -1.0,
-                        shot.rotation.to_radians().sin() * METEORS_SPEED * -1.0,
-                    ),
-                radius: kind.get_radius(),
-                active: true,
-                kind,
-                color: Color::BLUE,
+    /// Corners of the outline, rotated and placed around `center`.
+    pub fn vertices_at(&self, center: Vector2<f32>) -> [Vector2<f32>; OUTLINE_VERTICES] {
+        let rotation = Rotation2::new(self.rotation);
+
+        self.outline.map(|corner| center + rotation * corner)
+    }
+
+    /// Whether the outline overlaps the circle of `center` and `radius`, in a
+    /// world of `world_size` wrapping around its edges.
+    pub fn collides_with(&self, center: Vector2<f32>, radius: f32, world_size: (f32, f32)) -> bool {
+        let offset = collision::wrapped_offset(self.position, center, world_size);
+
+        offset.norm() <= self.radius + radius
+            && collision::polygon_circle(
+                &self.vertices_at(self.position),
+                self.position + offset,
+                radius,
+            )
+    }
+
+    /// Whether the outline overlaps `polygon`, which is placed around
+    /// `position`, in a world of `world_size` wrapping around its edges.
+    pub fn collides_with_polygon(
+        &self,
+        polygon: &[Vector2<f32>],
+        position: Vector2<f32>,
+        world_size: (f32, f32),
+    ) -> bool {
+        // Test the copy of the meteor closest to the polygon
+        let center = position + collision::wrapped_offset(position, self.position, world_size);
+
+        collision::polygons(&self.vertices_at(center), polygon)
+    }
+
+    /// Split the meteor in `fragments` smaller parts, if possible, after being
+    /// hit by something heading towards `rotation` (in degrees).
+    ///
+    /// Fragments keep the meteor velocity and fly apart mostly across the
+    /// impact. Their spreads cancel out, so together they carry on with the
+    /// momentum of the meteor.
+    pub fn split(&self, rotation: f32, fragments: u8, rng: &mut fastrand::Rng) -> Vec<Self> {
+        let Some(kind) = self.kind.next() else {
+            return Vec::new();
+        };
+
+        // Impact direction on screen, and the one across it
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        let impact = Vector2::new(sin, -cos);
+        let across = Vector2::new(-impact.y, impact.x);
+
+        let speeds = kind.spread_speed();
+        let speed = speeds.start() + rng.f32() * (speeds.end() - speeds.start());
+        let jitter = (rng.f32() * 2.0 - 1.0) * SPREAD_JITTER;
+
+        // Fragments are evenly spread around a circle squashed along the
+        // impact, whose directions sum to nothing.
+        (0..fragments)
+            .map(|i| {
+                let angle = jitter + TAU * i as f32 / fragments as f32;
+                let direction = across * angle.cos() + impact * angle.sin() * IMPACT_SPREAD;
+
+                Self::new(kind, self.position, self.speed + direction * speed, rng)
             })
-        })
+            .collect()
     }
 }
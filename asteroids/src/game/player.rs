@@ -1,7 +1,33 @@
-use nalgebra::{Vector2, Vector3};
-use raylib::{ffi::KeyboardKey, prelude::*};
+use nalgebra::Vector2;
+use raylib::prelude::*;
 
-use super::{PLAYER_SPEED, SHIP_HEIGHT};
+use super::{collision, Input, Weapon, PLAYER_SPEED, SHIP_HEIGHT};
+
+/// Speed gained per tick of thrust in the newtonian flight model.
+const THRUST: f32 = 0.12;
+/// Fraction of its speed the ship keeps every tick when drifting.
+const DRAG: f32 = 0.992;
+/// Fraction of its speed the ship keeps every tick when braking.
+const BRAKE_DRAG: f32 = 0.95;
+
+/// Shield energy used per tick, a full charge lasts three seconds.
+const SHIELD_DRAIN: f32 = 1.0 / 180.0;
+/// Shield energy regained per tick while it is down.
+const SHIELD_RECHARGE: f32 = 1.0 / 900.0;
+/// Energy the shield must get back to once emptied before it can be raised
+/// again.
+const SHIELD_RESTART: f32 = 0.25;
+
+/// How the ship responds to thrust.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FlightModel {
+    /// The ship keeps its momentum, thrust pushes it along its heading and
+    /// drag slowly brings it to a stop.
+    #[default]
+    Newtonian,
+    /// The ship always moves where it points, thrust only sets how fast.
+    Arcade,
+}
 
 #[derive(Default)]
 pub struct Player {
@@ -9,47 +35,175 @@ pub struct Player {
     pub speed: Vector2<f32>,
     pub acceleration: f32,
     pub rotation: f32,
-    pub collider: Vector3<f32>,
     pub color: Color,
+    pub flight_model: FlightModel,
+    pub weapon: Weapon,
+    /// Ticks left before the weapon can fire again.
+    pub cooldown: u32,
+    pub lives: u32,
+    /// Whether the ship is on the field, it is not while waiting to respawn.
+    pub alive: bool,
+    /// Ticks left before meteors can hit the ship again.
+    pub invulnerable: u32,
+    /// Whether the shield is up this tick.
+    pub shielded: bool,
+    /// Shield charge, from 0 to 1.
+    pub shield_energy: f32,
+    /// Whether the shield ran out and is still recharging up to
+    /// `SHIELD_RESTART`.
+    pub shield_locked: bool,
+    /// Ticks left before the next hyperspace jump.
+    pub hyperspace_cooldown: u32,
+    /// Where the last hyperspace jump started, shown while `warp_ticks` lasts.
+    pub warp_from: Vector2<f32>,
+    pub warp_ticks: u32,
 }
 
 impl Player {
-    pub fn process_player_movement(&mut self, rl: &RaylibHandle, screen_size: (f32, f32)) {
-        let (width, height) = screen_size;
+    /// Nose, left and right corners of the ship, as drawn and as used for
+    /// collisions.
+    pub fn vertices(&self) -> [Vector2<f32>; 3] {
+        let cosf = f32::cos(self.rotation.to_radians());
+        let sinf = f32::sin(self.rotation.to_radians());
+
+        [
+            self.position + Vector2::new(sinf * SHIP_HEIGHT, -cosf * SHIP_HEIGHT),
+            self.position + Vector2::new(-cosf * 10f32, -sinf * 10f32),
+            self.position + Vector2::new(cosf * 10f32, sinf * 10f32),
+        ]
+    }
+
+    /// Center of the shield, the centroid of the ship for the shield to cover
+    /// its nose as well as its base.
+    pub fn shield_center(&self) -> Vector2<f32> {
+        let [nose, left, right] = self.vertices();
+
+        (nose + left + right) / 3.0
+    }
+
+    /// Whether the ship overlaps the circle of `center` and `radius`, in a
+    /// world of `world_size` wrapping around its edges.
+    pub fn collides_with(&self, center: Vector2<f32>, radius: f32, world_size: (f32, f32)) -> bool {
+        // Test against the copy of the circle closest to the ship
+        let center = self.position + collision::wrapped_offset(self.position, center, world_size);
+
+        collision::polygon_circle(&self.vertices(), center, radius)
+    }
+
+    /// Distance covered in a tick, in screen coordinates.
+    pub fn velocity(&self) -> Vector2<f32> {
+        let speed = match self.flight_model {
+            FlightModel::Newtonian => self.speed,
+            FlightModel::Arcade => self.speed * self.acceleration,
+        };
+
+        Vector2::new(speed.x, -speed.y)
+    }
+
+    /// Raise the shield while its key is held and it has energy left,
+    /// otherwise let it recharge. Once emptied, it stays down until it has
+    /// recharged a bit.
+    pub fn process_player_shield(&mut self, input: &Input) {
+        if self.shield_locked && self.shield_energy >= SHIELD_RESTART {
+            self.shield_locked = false;
+        }
 
-        if rl.is_key_down(KeyboardKey::KEY_LEFT) {
+        self.shielded = input.shield && !self.shield_locked && self.shield_energy > 0.0;
+
+        if self.shielded {
+            self.shield_energy = f32::max(0.0, self.shield_energy - SHIELD_DRAIN);
+            self.shield_locked = self.shield_energy == 0.0;
+        } else {
+            self.shield_energy = f32::min(1.0, self.shield_energy + SHIELD_RECHARGE);
+        }
+    }
+
+    pub fn process_player_movement(&mut self, input: &Input, screen_size: (f32, f32)) {
+        if input.rotate_left {
             self.rotation -= 5f32;
         }
-        if rl.is_key_down(KeyboardKey::KEY_RIGHT) {
+        if input.rotate_right {
             self.rotation += 5f32;
         }
 
-        self.speed.x = self.rotation.to_radians().sin() * PLAYER_SPEED;
-        self.speed.y = self.rotation.to_radians().cos() * PLAYER_SPEED;
+        let heading = Vector2::new(
+            self.rotation.to_radians().sin(),
+            self.rotation.to_radians().cos(),
+        );
 
-        if rl.is_key_down(KeyboardKey::KEY_UP) {
-            self.acceleration = f32::min(self.acceleration + 0.04, 1.0);
-        } else {
-            self.acceleration = f32::max(0.0, self.acceleration - 0.03);
-        }
+        match self.flight_model {
+            FlightModel::Newtonian => {
+                if input.thrust {
+                    self.speed += heading * THRUST;
+                }
+
+                self.speed *= if input.brake { BRAKE_DRAG } else { DRAG };
+                self.speed = self.speed.cap_magnitude(PLAYER_SPEED);
+
+                self.position.x += self.speed.x;
+                self.position.y -= self.speed.y;
+            }
+            FlightModel::Arcade => {
+                self.speed = heading * PLAYER_SPEED;
 
-        if rl.is_key_down(KeyboardKey::KEY_DOWN) {
-            self.acceleration = f32::max(0.0, self.acceleration - 0.04);
+                if input.thrust {
+                    self.acceleration = f32::min(self.acceleration + 0.04, 1.0);
+                } else {
+                    self.acceleration = f32::max(0.0, self.acceleration - 0.03);
+                }
+
+                if input.brake {
+                    self.acceleration = f32::max(0.0, self.acceleration - 0.04);
+                }
+
+                self.position.x += self.speed.x * self.acceleration;
+                self.position.y -= self.speed.y * self.acceleration;
+            }
         }
 
-        self.position.x += self.speed.x * self.acceleration;
-        self.position.y -= self.speed.y * self.acceleration;
+        self.position = collision::wrap(self.position, screen_size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emptied_shield_stays_down_until_recharged() {
+        let mut player = Player {
+            shield_energy: 1.0,
+            ..Player::default()
+        };
+        let input = Input {
+            shield: true,
+            ..Input::default()
+        };
+
+        let mut ticks_up = 0;
+        while player.shield_energy > 0.0 {
+            player.process_player_shield(&input);
+            ticks_up += 1;
+        }
+        assert!((180..=181).contains(&ticks_up));
 
-        if self.position.x > width + SHIP_HEIGHT {
-            self.position.x = -SHIP_HEIGHT;
-        } else if self.position.x < -SHIP_HEIGHT {
-            self.position.x = width + SHIP_HEIGHT;
+        // Holding the key does not bring it back while it recharges
+        let mut ticks_down = 0;
+        loop {
+            player.process_player_shield(&input);
+            if player.shielded {
+                break;
+            }
+            ticks_down += 1;
         }
+        assert!(ticks_down >= (SHIELD_RESTART / SHIELD_RECHARGE) as i32);
 
-        if self.position.y > height + SHIP_HEIGHT {
-            self.position.y = -SHIP_HEIGHT;
-        } else if self.position.y < -SHIP_HEIGHT {
-            self.position.y = height + SHIP_HEIGHT;
+        // Then it lasts long enough not to flicker
+        let mut ticks_up = 0;
+        while player.shielded {
+            player.process_player_shield(&input);
+            ticks_up += 1;
         }
+        assert!(ticks_up >= (SHIELD_RESTART / SHIELD_DRAIN) as i32);
     }
 }
@@ -1,10 +1,20 @@
 use std::cell::RefMut;
 
+use common::input::Bindings;
+
 use super::*;
 use raylib::core::text::measure_text;
 
+use self::collision::ghost_offsets;
+
 impl Game {
-    pub fn draw(&self, rl: &RaylibHandle, d: &RefMut<RaylibDrawHandle>) {
+    /// Draw the game, telling the player what to press by the `bindings`.
+    pub fn draw(
+        &self,
+        rl: &RaylibHandle,
+        d: &RefMut<RaylibDrawHandle>,
+        bindings: &Bindings<Action>,
+    ) {
         let (width, height) = (rl.get_screen_width(), rl.get_screen_height());
 
         let half_width = width / 2;
@@ -13,38 +23,172 @@ impl Game {
         d.clear_background(Color::RAYWHITE);
 
         if !self.game_over {
-            let cosf = f32::cos(self.player.rotation.to_radians());
-            let sinf = f32::sin(self.player.rotation.to_radians());
+            // Anything close to an edge is also drawn on the opposite one
+            let ghosts = |position, radius| ghost_offsets(position, radius, self.world_size);
+
+            let tri = self.player.vertices();
+
+            // The ship blinks while invulnerable
+            if self.player.alive && self.player.invulnerable % 16 < 8 {
+                for offset in ghosts(self.player.position, SHIP_HEIGHT) {
+                    d.draw_triangle(
+                        tri[0] + offset,
+                        tri[1] + offset,
+                        tri[2] + offset,
+                        self.player.color,
+                    );
+                }
+            }
+
+            if self.player.alive && self.player.shielded {
+                let shield_center = self.player.shield_center();
 
-            let tri = [
-                self.player.position + Vector2::new(sinf * SHIP_HEIGHT, -cosf * SHIP_HEIGHT),
-                self.player.position + Vector2::new(-cosf * 10f32, -sinf * 10f32),
-                self.player.position + Vector2::new(cosf * 10f32, sinf * 10f32),
-            ];
+                for offset in ghosts(shield_center, SHIELD_RADIUS) {
+                    let center = shield_center + offset;
 
-            d.draw_triangle(tri[0], tri[1], tri[2], self.player.color);
+                    d.draw_circle_lines(
+                        center.x as i32,
+                        center.y as i32,
+                        SHIELD_RADIUS,
+                        Color::SKYBLUE,
+                    );
+                    d.draw_circle_lines(
+                        center.x as i32,
+                        center.y as i32,
+                        SHIELD_RADIUS - 2.0,
+                        Color::fade(&Color::SKYBLUE, 0.5),
+                    );
+                }
+            }
+
+            // Hyperspace jump: the ship fades out at its old spot and in at the new one
+            if self.player.warp_ticks > 0 {
+                let progress = self.player.warp_ticks as f32 / WARP_TICKS as f32;
+
+                for (center, radius) in [
+                    (self.player.warp_from, 4.0 + 20.0 * (1.0 - progress)),
+                    (self.player.position, 4.0 + 20.0 * progress),
+                ] {
+                    for offset in ghosts(center, radius) {
+                        d.draw_circle_lines(
+                            (center.x + offset.x) as i32,
+                            (center.y + offset.y) as i32,
+                            radius,
+                            Color::fade(&Color::VIOLET, progress),
+                        );
+                    }
+                }
+            }
 
+            // Draw meteors as line art of their outline
             for meteor in &self.meteors {
-                d.draw_circle_v(
-                    meteor.position,
-                    meteor.radius,
-                    if meteor.active {
-                        meteor.color
-                    } else {
-                        Color::fade(&Color::LIGHTGRAY, 0.3)
-                    },
-                );
+                let color = if meteor.active {
+                    meteor.color
+                } else {
+                    Color::fade(&Color::LIGHTGRAY, 0.3)
+                };
+
+                for offset in ghosts(meteor.position, meteor.radius) {
+                    let outline = meteor.vertices_at(meteor.position + offset);
+
+                    for (i, point) in outline.iter().enumerate() {
+                        d.draw_line_v(*point, outline[(i + 1) % outline.len()], color);
+                    }
+                }
             }
 
-            for shot in &self.shots {
+            for shot in self.shots.iter().chain(&self.saucer_shots) {
                 d.draw_circle_v(shot.position, shot.radius, shot.color);
             }
 
-            if self.victory {
+            // Draw saucers as line art: dome, rim and hull
+            for saucer in &self.saucers {
+                let r = saucer.kind.radius();
+                let outline = [
+                    (-1.0, 0.0),
+                    (-0.4, -0.3),
+                    (-0.2, -0.6),
+                    (0.2, -0.6),
+                    (0.4, -0.3),
+                    (1.0, 0.0),
+                    (0.5, 0.35),
+                    (-0.5, 0.35),
+                ]
+                .map(|(x, y)| Vector2::new(x, y) * r);
+
+                for offset in ghosts(saucer.position, r) {
+                    let outline = outline.map(|point| saucer.position + offset + point);
+
+                    for (i, point) in outline.iter().enumerate() {
+                        d.draw_line_v(*point, outline[(i + 1) % outline.len()], Color::DARKGREEN);
+                    }
+                    d.draw_line_v(outline[1], outline[4], Color::DARKGREEN);
+                    d.draw_line_v(outline[0], outline[5], Color::DARKGREEN);
+                }
+            }
+
+            d.draw_text(&format!("SCORE: {}", self.score), 10, 10, 20, Color::GRAY);
+
+            // Draw shield energy, grayed out until it can be raised again
+            d.draw_rectangle_lines(width - 110, height - 20, 100, 10, Color::LIGHTGRAY);
+            d.draw_rectangle(
+                width - 110,
+                height - 20,
+                (100.0 * self.player.shield_energy) as i32,
+                10,
+                if self.player.shield_locked {
+                    Color::LIGHTGRAY
+                } else {
+                    Color::SKYBLUE
+                },
+            );
+
+            // Draw lives left as small ships
+            for i in 0..self.player.lives {
+                let base = Vector2::new(20.0 + 20.0 * i as f32, height as f32 - 10.0);
+
+                d.draw_triangle(
+                    base + Vector2::new(0.0, -16.0),
+                    base + Vector2::new(-6.0, 0.0),
+                    base + Vector2::new(6.0, 0.0),
+                    Color::LIGHTGRAY,
+                );
+            }
+
+            if self.combo > 1 {
+                d.draw_text(&format!("COMBO x{}", self.combo), 10, 35, 10, Color::MAROON);
+            }
+
+            let wave = format!("WAVE {}", self.wave);
+            d.draw_text(
+                &wave,
+                width - measure_text(&wave, 20) - 10,
+                10,
+                20,
+                Color::GRAY,
+            );
+
+            let weapon = self.player.weapon.name();
+            d.draw_text(
+                weapon,
+                width - measure_text(weapon, 10) - 10,
+                35,
+                10,
+                self.player.weapon.color(),
+            );
+
+            if self.wave_ticks > 0 {
+                d.draw_text(
+                    &wave,
+                    half_width - measure_text(&wave, 40) / 2,
+                    half_height - 80,
+                    40,
+                    Color::DARKGRAY,
+                );
                 d.draw_text(
-                    "VICTORY",
-                    half_width - measure_text("VICTORY", 20) / 2,
-                    half_height,
+                    "GET READY",
+                    half_width - measure_text("GET READY", 20) / 2,
+                    half_height - 30,
                     20,
                     Color::LIGHTGRAY,
                 );
@@ -60,13 +204,35 @@ impl Game {
                 );
             }
         } else {
+            let score = format!("SCORE: {}", self.score);
             d.draw_text(
-                "PRESS [ENTER] TO PLAY AGAIN",
-                half_width - measure_text("PRESS [ENTER] TO PLAY AGAIN", 20) / 2,
+                &score,
+                half_width - measure_text(&score, 40) / 2,
+                half_height - 110,
+                40,
+                Color::DARKGRAY,
+            );
+
+            let again = format!(
+                "PRESS [{}] TO PLAY AGAIN",
+                bindings.describe(Action::Restart)
+            );
+            d.draw_text(
+                &again,
+                half_width - measure_text(&again, 20) / 2,
                 half_height - 50,
                 20,
                 Color::GRAY,
             );
+
+            let seed = format!("SEED: {}", self.seed);
+            d.draw_text(
+                &seed,
+                half_width - measure_text(&seed, 10) / 2,
+                half_height - 20,
+                10,
+                Color::LIGHTGRAY,
+            );
         }
     }
 }
@@ -0,0 +1,325 @@
+use std::f32::consts::TAU;
+
+use nalgebra::{Rotation2, Vector2};
+use raylib::prelude::*;
+
+use super::{collision, *};
+
+/// Time between two saucers, in ticks.
+const SAUCER_DELAY: std::ops::RangeInclusive<u32> = 600..=1200;
+/// Score from which small saucers become the most common.
+const SMALL_SAUCER_SCORE: u32 = 10000;
+const SAUCER_SHOT_SPEED: f32 = 5f32;
+const SAUCER_SHOT_LIFE: u8 = 80;
+/// How far off target a small saucer may shoot, in radians.
+const SAUCER_AIM_ERROR: f32 = 0.05;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SaucerKind {
+    /// Big and slow, fires at random.
+    Large,
+    /// Small and fast, aims where the ship is heading.
+    Small,
+}
+
+impl SaucerKind {
+    pub fn radius(self) -> f32 {
+        match self {
+            SaucerKind::Large => 20.0,
+            SaucerKind::Small => 10.0,
+        }
+    }
+
+    pub fn speed(self) -> f32 {
+        match self {
+            SaucerKind::Large => 1.5,
+            SaucerKind::Small => 2.5,
+        }
+    }
+
+    pub fn points(self) -> u32 {
+        match self {
+            SaucerKind::Large => 200,
+            SaucerKind::Small => 1000,
+        }
+    }
+
+    /// Ticks between two shots.
+    pub fn fire_interval(self) -> u32 {
+        match self {
+            SaucerKind::Large => 90,
+            SaucerKind::Small => 60,
+        }
+    }
+}
+
+/// A flying saucer, crossing the screen from one side to the other.
+pub struct Saucer {
+    pub kind: SaucerKind,
+    pub position: Vector2<f32>,
+    pub speed: Vector2<f32>,
+    /// Ticks left before the saucer changes course.
+    pub turn_ticks: u32,
+    /// Ticks left before the saucer fires.
+    pub fire_ticks: u32,
+    /// Horizontal distance covered since the saucer came in.
+    pub travel: f32,
+}
+
+impl Saucer {
+    /// Move the saucer, returns whether it has yet to cross the whole screen.
+    pub fn update(&mut self, world_size: (f32, f32)) -> bool {
+        self.position = collision::wrap(self.position + self.speed, world_size);
+        self.travel += self.speed.x.abs();
+
+        self.travel < world_size.0
+    }
+}
+
+impl Game {
+    pub(crate) fn reset_saucers(&mut self) {
+        self.saucers.clear();
+        self.saucer_shots.clear();
+        self.saucer_ticks = self.rng.u32(SAUCER_DELAY);
+    }
+
+    /// Send a saucer now and then, move them and make them fire.
+    pub(crate) fn update_saucers(&mut self) {
+        let world_size = self.world_size;
+
+        // No saucer between waves
+        if self.saucers.is_empty() && self.wave_ticks == 0 {
+            self.saucer_ticks = self.saucer_ticks.saturating_sub(1);
+
+            if self.saucer_ticks == 0 {
+                self.spawn_saucer();
+            }
+        }
+
+        let mut saucers = std::mem::take(&mut self.saucers);
+
+        saucers.retain_mut(|saucer| {
+            saucer.turn_ticks -= 1;
+            if saucer.turn_ticks == 0 {
+                let course = self.rng.i32(-1..=1) as f32;
+
+                saucer.speed.y = course * saucer.speed.x.abs() * 0.7;
+                saucer.turn_ticks = self.rng.u32(60..=120);
+            }
+
+            saucer.fire_ticks -= 1;
+            if saucer.fire_ticks == 0 {
+                self.saucer_fire(saucer);
+                saucer.fire_ticks = saucer.kind.fire_interval();
+            }
+
+            saucer.update(world_size)
+        });
+
+        self.saucers = saucers;
+    }
+
+    fn spawn_saucer(&mut self) {
+        let (width, height) = self.world_size;
+
+        let small_chance = if self.score >= SMALL_SAUCER_SCORE {
+            0.7
+        } else {
+            0.2
+        };
+        let kind = if self.rng.f32() < small_chance {
+            SaucerKind::Small
+        } else {
+            SaucerKind::Large
+        };
+
+        // Come in from either side, across the seam of the screen
+        let (x, direction) = if self.rng.bool() {
+            (0f32, 1f32)
+        } else {
+            (width, -1f32)
+        };
+        let y = height * (0.1 + 0.8 * self.rng.f32());
+
+        self.saucers.push(Saucer {
+            kind,
+            position: Vector2::new(x, y),
+            speed: Vector2::new(direction * kind.speed(), 0f32),
+            turn_ticks: self.rng.u32(60..=120),
+            fire_ticks: kind.fire_interval(),
+            travel: 0f32,
+        });
+        self.saucer_ticks = self.rng.u32(SAUCER_DELAY);
+
+        self.events.push(Event::SaucerSpawned { kind });
+    }
+
+    fn saucer_fire(&mut self, saucer: &Saucer) {
+        let direction = match saucer.kind {
+            SaucerKind::Small if self.player.alive => {
+                let aim = self.lead_target(saucer.position);
+                let error = (self.rng.f32() * 2.0 - 1.0) * SAUCER_AIM_ERROR;
+
+                Rotation2::new(error) * aim
+            }
+            _ => Rotation2::new(self.rng.f32() * TAU) * Vector2::new(1f32, 0f32),
+        };
+
+        // Shots move with y pointing up
+        let speed = direction * SAUCER_SHOT_SPEED;
+
+        self.saucer_shots.push(Shoot {
+            position: saucer.position,
+            speed: Vector2::new(speed.x, -speed.y),
+            radius: 2f32,
+            rotation: speed.x.atan2(-speed.y).to_degrees(),
+            life: SAUCER_SHOT_LIFE,
+            color: Color::RED,
+            weapon: Weapon::Blaster,
+            hits: 1,
+        });
+
+        self.events.push(Event::SaucerShot);
+    }
+
+    /// Direction to shoot from `origin` to hit the ship, accounting for its
+    /// velocity.
+    fn lead_target(&self, origin: Vector2<f32>) -> Vector2<f32> {
+        let offset = collision::wrapped_offset(origin, self.player.position, self.world_size);
+        let velocity = self.player.velocity();
+
+        // Solve |offset + velocity * t| = SAUCER_SHOT_SPEED * t for the
+        // earliest t > 0.
+        let a = velocity.norm_squared() - SAUCER_SHOT_SPEED * SAUCER_SHOT_SPEED;
+        let b = 2.0 * offset.dot(&velocity);
+        let c = offset.norm_squared();
+        let discriminant = b * b - 4.0 * a * c;
+
+        let time = if a.abs() < f32::EPSILON {
+            -c / b
+        } else if discriminant >= 0.0 {
+            let root = discriminant.sqrt();
+            [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+                .into_iter()
+                .filter(|t| *t > 0.0)
+                .reduce(f32::min)
+                .unwrap_or(0.0)
+        } else {
+            0.0
+        };
+
+        let aim = offset + velocity * time.max(0.0);
+
+        if aim.norm() > 0.0 {
+            aim.normalize()
+        } else {
+            Vector2::new(1f32, 0f32)
+        }
+    }
+
+    /// Saucer shots, saucers, meteors and the ship all destroy each other.
+    pub(crate) fn check_saucer_collisions(&mut self) {
+        let world_size = self.world_size;
+
+        // Saucer shots vs meteors and ship
+        let mut shots = std::mem::take(&mut self.saucer_shots);
+
+        shots.retain_mut(|shot| {
+            shot.life -= 1;
+
+            shot.position.x += shot.speed.x;
+            shot.position.y -= shot.speed.y;
+
+            if shot.is_off_screen(world_size) || shot.life == 0 {
+                return false;
+            }
+
+            if let Some(index) = self.meteors.iter().position(|meteor| {
+                meteor.active && meteor.collides_with(shot.position, shot.radius, world_size)
+            }) {
+                self.break_meteor(index, shot.rotation);
+                return false;
+            }
+
+            if self.player.alive
+                && self.player.shielded
+                && collision::circles(
+                    shot.position,
+                    shot.radius,
+                    self.player.shield_center(),
+                    SHIELD_RADIUS,
+                    world_size,
+                )
+            {
+                self.events.push(Event::ShieldHit);
+                return false;
+            }
+
+            // The shield covers the whole ship
+            if self.player.alive
+                && !self.player.shielded
+                && self.player.invulnerable == 0
+                && self
+                    .player
+                    .collides_with(shot.position, shot.radius, world_size)
+            {
+                self.destroy_ship();
+                return false;
+            }
+
+            true
+        });
+
+        self.saucer_shots = shots;
+
+        // Saucers vs meteors and ship
+        let mut saucers = std::mem::take(&mut self.saucers);
+
+        saucers.retain(|saucer| {
+            let radius = saucer.kind.radius();
+
+            if let Some(index) = self.meteors.iter().position(|meteor| {
+                meteor.active && meteor.collides_with(saucer.position, radius, world_size)
+            }) {
+                // A single explosion for both, reported by the meteor
+                self.break_meteor(index, saucer.speed.x.atan2(-saucer.speed.y).to_degrees());
+                return false;
+            }
+
+            // Ramming the shield destroys the saucer only
+            if self.player.alive
+                && self.player.shielded
+                && collision::circles(
+                    saucer.position,
+                    radius,
+                    self.player.shield_center(),
+                    SHIELD_RADIUS,
+                    world_size,
+                )
+            {
+                self.events.push(Event::ShieldHit);
+                self.events.push(Event::Explosion {
+                    variant: self.rng.u32(..),
+                });
+                self.score += saucer.kind.points();
+                return false;
+            }
+
+            if self.player.alive
+                && !self.player.shielded
+                && self.player.invulnerable == 0
+                && self
+                    .player
+                    .collides_with(saucer.position, radius, world_size)
+            {
+                self.score += saucer.kind.points();
+                self.destroy_ship();
+                return false;
+            }
+
+            true
+        });
+
+        self.saucers = saucers;
+    }
+}
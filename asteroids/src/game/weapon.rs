@@ -0,0 +1,111 @@
+use raylib::prelude::*;
+
+/// Weapons of the ship, cycled through with the switch key.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Weapon {
+    #[default]
+    Blaster,
+    /// Three shots in a fan.
+    Spread,
+    /// Short ranged, but fires as long as the key is held.
+    Rapid,
+    /// Goes through several meteors.
+    Laser,
+    /// Slow, but steers towards the closest meteor.
+    Homing,
+}
+
+impl Weapon {
+    pub const ALL: [Weapon; 5] = [
+        Weapon::Blaster,
+        Weapon::Spread,
+        Weapon::Rapid,
+        Weapon::Laser,
+        Weapon::Homing,
+    ];
+
+    pub fn next(self) -> Self {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Weapon::Blaster => "BLASTER",
+            Weapon::Spread => "SPREAD",
+            Weapon::Rapid => "RAPID",
+            Weapon::Laser => "LASER",
+            Weapon::Homing => "HOMING",
+        }
+    }
+
+    /// Whether the weapon keeps firing while the key is held, the others fire
+    /// once per press.
+    pub fn automatic(self) -> bool {
+        self == Weapon::Rapid
+    }
+
+    /// Ticks to wait between two shots.
+    pub fn cooldown(self) -> u32 {
+        match self {
+            Weapon::Blaster => 10,
+            Weapon::Spread => 20,
+            Weapon::Rapid => 4,
+            Weapon::Laser => 30,
+            Weapon::Homing => 30,
+        }
+    }
+
+    /// Ticks a shot lives.
+    pub fn life(self) -> u8 {
+        match self {
+            Weapon::Blaster => 60,
+            Weapon::Spread => 40,
+            Weapon::Rapid => 35,
+            Weapon::Laser => 30,
+            Weapon::Homing => 120,
+        }
+    }
+
+    pub fn radius(self) -> f32 {
+        match self {
+            Weapon::Blaster | Weapon::Spread => 2.0,
+            Weapon::Rapid => 1.5,
+            Weapon::Laser => 3.0,
+            Weapon::Homing => 4.0,
+        }
+    }
+
+    /// Shot speed, relative to the ship top speed.
+    pub fn speed(self) -> f32 {
+        match self {
+            Weapon::Blaster | Weapon::Spread => 1.5,
+            Weapon::Rapid => 2.0,
+            Weapon::Laser => 3.0,
+            Weapon::Homing => 1.0,
+        }
+    }
+
+    /// Directions of the shots fired at once, in degrees from the ship heading.
+    pub fn angles(self) -> &'static [f32] {
+        match self {
+            Weapon::Spread => &[-15.0, 0.0, 15.0],
+            _ => &[0.0],
+        }
+    }
+
+    /// Meteors a single shot can destroy.
+    pub fn hits(self) -> u8 {
+        match self {
+            Weapon::Laser => 3,
+            _ => 1,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Weapon::Blaster | Weapon::Spread | Weapon::Rapid => Color::BLACK,
+            Weapon::Laser => Color::RED,
+            Weapon::Homing => Color::ORANGE,
+        }
+    }
+}
@@ -1,12 +1,28 @@
 use assets::Assets;
-use game::Game;
+use common::{
+    input::{Actions, Bindings},
+    scoreboard::ScoreBoard,
+};
+use game::{Action, Event, Game, Input, DEFAULT_FRAGMENTS};
+use options::Options;
+use replay::Replay;
 
 use raylib::audio::RaylibAudio;
 
 mod assets;
 mod game;
+mod options;
+mod replay;
 
 fn main() {
+    let options = match Options::from_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("asteroids: {err}");
+            std::process::exit(2);
+        }
+    };
+
     let rl = raylib::init()
         .title("Asteroids")
         .width(800)
@@ -17,13 +33,112 @@ fn main() {
 
     rl.set_target_fps(60);
 
-    let mut game = Game::default();
+    let playback = options
+        .replay
+        .as_ref()
+        .map(|path| match Replay::load(path) {
+            Ok(replay) => replay,
+            Err(err) => {
+                eprintln!("asteroids: cannot load replay {}: {err}", path.display());
+                std::process::exit(1);
+            }
+        });
+
+    let world_size = match &playback {
+        Some(replay) => replay.world_size,
+        None => (rl.get_screen_width() as f32, rl.get_screen_height() as f32),
+    };
+
+    let (seed, flight_model, fragments) = match &playback {
+        Some(replay) => (replay.seed, replay.flight_model, replay.fragments),
+        None => (
+            options.seed.unwrap_or_else(|| fastrand::u64(..)),
+            options.flight_model.unwrap_or_default(),
+            options.fragments.unwrap_or(DEFAULT_FRAGMENTS),
+        ),
+    };
+
+    let bindings = match &options.bindings {
+        Some(path) => match Bindings::load(path) {
+            Ok(bindings) => bindings,
+            Err(err) => {
+                eprintln!("asteroids: cannot load bindings {}: {err}", path.display());
+                std::process::exit(1);
+            }
+        },
+        None => Bindings::load_config("asteroids"),
+    };
+    let mut actions = Actions::<Action>::new(bindings);
+
+    let mut game = Game::new(seed, flight_model, fragments);
     let assets = Assets::load(&raudio);
+    let mut scoreboard = ScoreBoard::load("asteroids");
+
+    game.init(world_size);
+    println!("asteroids: seed {}", game.seed());
 
-    game.init(&rl);
+    let mut recording = options.record.as_ref().map(|_| {
+        Replay::new(
+            game.seed(),
+            game.flight_model(),
+            game.fragments(),
+            world_size,
+        )
+    });
+    let mut replayed_inputs = playback.as_ref().map(Replay::inputs);
 
     while !rl.window_should_close() {
-        game.update(&rl, &assets, &raudio);
-        rl.begin_drawing(|d| game.draw(&rl, &d))
+        actions.update(&rl);
+
+        // The keyboard goes to the name prompt while it is up, the game
+        // still ticks so that recordings stay in sync.
+        let input = match &mut replayed_inputs {
+            Some(inputs) => inputs.next().unwrap_or_default(),
+            None if scoreboard.is_entering_name() => {
+                scoreboard.update(&rl, actions.is_pressed(Action::Restart));
+                Input::default()
+            }
+            None => Input::poll(&actions),
+        };
+
+        if let Some(recording) = &mut recording {
+            recording.push(input);
+        }
+
+        game.update(&input);
+
+        for event in game.take_events() {
+            match event {
+                Event::Shot { weapon } => assets.play_shoot(&raudio, weapon),
+                Event::Explosion { variant } => assets.play_explosion(&raudio, variant),
+                Event::SaucerSpawned { kind } => assets.play_saucer(&raudio, kind),
+                Event::SaucerShot => assets.play_saucer_shot(&raudio),
+                Event::Hyperspace => assets.play_hyperspace(&raudio),
+                Event::ShieldHit => assets.play_shield(&raudio),
+                Event::ExtraLife => assets.play_extra_life(&raudio),
+                // Replayed sessions do not make it in the high scores.
+                Event::GameOver { score } if replayed_inputs.is_none() => scoreboard.submit(score),
+                Event::GameOver { .. } => {}
+            }
+        }
+
+        rl.begin_drawing(|d| {
+            game.draw(&rl, &d, actions.bindings());
+
+            if game.is_over() {
+                scoreboard.draw(
+                    &d,
+                    (rl.get_screen_width(), rl.get_screen_height()),
+                    &actions.bindings().describe(Action::Restart),
+                );
+            }
+        })
+    }
+
+    if let (Some(path), Some(recording)) = (&options.record, &recording) {
+        match recording.save(path) {
+            Ok(()) => println!("asteroids: replay saved to {}", path.display()),
+            Err(err) => eprintln!("asteroids: cannot save replay {}: {err}", path.display()),
+        }
     }
 }
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use crate::game::{FlightModel, MAX_FRAGMENTS};
+
+/// Command line options.
+#[derive(Default)]
+pub struct Options {
+    /// Seed for the meteor layout, random when not given.
+    pub seed: Option<u64>,
+    /// How the ship flies, newtonian when not given.
+    pub flight_model: Option<FlightModel>,
+    /// Pieces meteors break into, two when not given.
+    pub fragments: Option<u8>,
+    /// Read the bindings from this file instead of the user config directory.
+    pub bindings: Option<PathBuf>,
+    /// Record the session inputs to this file.
+    pub record: Option<PathBuf>,
+    /// Play back a previously recorded session instead of reading the keyboard.
+    pub replay: Option<PathBuf>,
+}
+
+impl Options {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().ok_or("--seed expects a value")?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("invalid seed '{value}'"))?;
+                    options.seed = Some(seed);
+                }
+                "--flight" => {
+                    let value = args.next().ok_or("--flight expects a model")?;
+                    let flight_model = match value.as_str() {
+                        "newtonian" => FlightModel::Newtonian,
+                        "arcade" => FlightModel::Arcade,
+                        _ => {
+                            return Err(format!(
+                                "invalid flight model '{value}', expected 'newtonian' or 'arcade'"
+                            ))
+                        }
+                    };
+                    options.flight_model = Some(flight_model);
+                }
+                "--fragments" => {
+                    let value = args.next().ok_or("--fragments expects a value")?;
+                    let fragments = value
+                        .parse()
+                        .ok()
+                        .filter(|fragments| (2..=MAX_FRAGMENTS).contains(fragments))
+                        .ok_or_else(|| {
+                            format!("invalid fragments '{value}', expected 2 to {MAX_FRAGMENTS}")
+                        })?;
+                    options.fragments = Some(fragments);
+                }
+                "--bindings" => {
+                    let path = args.next().ok_or("--bindings expects a file")?;
+                    options.bindings = Some(path.into());
+                }
+                "--record" => {
+                    let path = args.next().ok_or("--record expects a file")?;
+                    options.record = Some(path.into());
+                }
+                "--replay" => {
+                    let path = args.next().ok_or("--replay expects a file")?;
+                    options.replay = Some(path.into());
+                }
+                _ => return Err(format!("unknown argument '{arg}'")),
+            }
+        }
+
+        if options.replay.is_some() && options.seed.is_some() {
+            return Err(
+                "--seed cannot be used with --replay, the seed is stored in the replay".into(),
+            );
+        }
+
+        if options.replay.is_some() && options.flight_model.is_some() {
+            return Err(
+                "--flight cannot be used with --replay, the flight model is stored in the replay"
+                    .into(),
+            );
+        }
+
+        if options.replay.is_some() && options.fragments.is_some() {
+            return Err(
+                "--fragments cannot be used with --replay, the fragments are stored in the replay"
+                    .into(),
+            );
+        }
+
+        Ok(options)
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::game::{FlightModel, Input, MAX_FRAGMENTS};
+
+const MAGIC: &[u8; 4] = b"ASTR";
+/// Bumped whenever the file layout or the simulation changes, including the
+/// order of random draws: older replays would load fine and then play out
+/// differently.
+const VERSION: u8 = 9;
+/// Smallest world width and height a replay may ask for: meteors spawn at
+/// least 150 pixels away from the ship along both axes, which a smaller world
+/// has no room for.
+const MIN_WORLD_SIZE: f32 = 300.0;
+
+/// A recorded session: the seed, flight model, meteor fragments and world size
+/// the game was started with and the inputs of every tick.
+///
+/// On disk, after the header, inputs are run-length encoded as pairs of
+/// little-endian `u16` (input bits, repeat count), which keeps long stretches
+/// of holding the same keys down to four bytes.
+pub struct Replay {
+    pub seed: u64,
+    pub flight_model: FlightModel,
+    pub fragments: u8,
+    pub world_size: (f32, f32),
+    inputs: Vec<Input>,
+}
+
+impl Replay {
+    pub fn new(
+        seed: u64,
+        flight_model: FlightModel,
+        fragments: u8,
+        world_size: (f32, f32),
+    ) -> Self {
+        Self {
+            seed,
+            flight_model,
+            fragments,
+            world_size,
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Append the inputs of one tick.
+    pub fn push(&mut self, input: Input) {
+        self.inputs.push(input);
+    }
+
+    /// Recorded inputs, one per tick.
+    pub fn inputs(&self) -> impl Iterator<Item = Input> + '_ {
+        self.inputs.iter().copied()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&self.seed.to_le_bytes())?;
+        writer.write_all(&[match self.flight_model {
+            FlightModel::Newtonian => 0,
+            FlightModel::Arcade => 1,
+        }])?;
+        writer.write_all(&[self.fragments])?;
+        writer.write_all(&self.world_size.0.to_le_bytes())?;
+        writer.write_all(&self.world_size.1.to_le_bytes())?;
+
+        let mut inputs = self.inputs.iter().map(|input| input.to_bits()).peekable();
+
+        while let Some(bits) = inputs.next() {
+            let mut count: u16 = 1;
+
+            while count < u16::MAX && inputs.next_if_eq(&bits).is_some() {
+                count += 1;
+            }
+
+            writer.write_all(&bits.to_le_bytes())?;
+            writer.write_all(&count.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not an asteroids replay"));
+        }
+
+        let mut version = [0; 1];
+        reader.read_exact(&mut version)?;
+        if version[0] != VERSION {
+            return Err(invalid_data(format!(
+                "unsupported replay version {}",
+                version[0]
+            )));
+        }
+
+        let mut seed = [0; 8];
+        reader.read_exact(&mut seed)?;
+
+        let mut flight_model = [0; 1];
+        reader.read_exact(&mut flight_model)?;
+        let flight_model = match flight_model[0] {
+            0 => FlightModel::Newtonian,
+            1 => FlightModel::Arcade,
+            model => return Err(invalid_data(format!("unknown flight model {model}"))),
+        };
+
+        let mut fragments = [0; 1];
+        reader.read_exact(&mut fragments)?;
+        if !(2..=MAX_FRAGMENTS).contains(&fragments[0]) {
+            return Err(invalid_data(format!(
+                "invalid meteor fragments {}",
+                fragments[0]
+            )));
+        }
+
+        let mut width = [0; 4];
+        let mut height = [0; 4];
+        reader.read_exact(&mut width)?;
+        reader.read_exact(&mut height)?;
+        let (width, height) = (f32::from_le_bytes(width), f32::from_le_bytes(height));
+        let valid = |size: f32| size.is_finite() && size >= MIN_WORLD_SIZE;
+        if !valid(width) || !valid(height) {
+            return Err(invalid_data(format!("invalid world size {width}x{height}")));
+        }
+
+        let mut replay = Self::new(
+            u64::from_le_bytes(seed),
+            flight_model,
+            fragments[0],
+            (width, height),
+        );
+
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)?;
+
+        if body.len() % 4 != 0 {
+            return Err(invalid_data("truncated replay"));
+        }
+
+        for run in body.chunks_exact(4) {
+            let bits = u16::from_le_bytes([run[0], run[1]]);
+            let count = u16::from_le_bytes([run[2], run[3]]);
+
+            replay
+                .inputs
+                .extend(std::iter::repeat_n(Input::from_bits(bits), count as usize));
+        }
+
+        Ok(replay)
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
@@ -0,0 +1,238 @@
+//! High-score tables.
+//!
+//! Every game saves its table in the same text format, one `score name` entry
+//! per line, best first:
+//!
+//! ```text
+//! # high scores
+//! 12500 ALICE
+//! 9800 BOB
+//! ```
+//!
+//! Lines starting with '#' and empty lines are ignored.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::invalid_data;
+
+/// Entries kept in a table.
+pub const TABLE_SIZE: usize = 10;
+/// Longest name that can be entered.
+pub const NAME_LENGTH: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub score: u32,
+}
+
+/// The best scores of a game, best first.
+#[derive(Default, Debug)]
+pub struct HighScores {
+    entries: Vec<Entry>,
+}
+
+impl HighScores {
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Whether `score` earns a place in the table.
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < TABLE_SIZE
+                || self.entries.last().is_some_and(|last| score > last.score))
+    }
+
+    /// Add an entry, returns its rank or `None` if it does not make it in the
+    /// table. Ties rank below the scores already there.
+    pub fn insert(&mut self, name: impl Into<String>, score: u32) -> Option<usize> {
+        if !self.qualifies(score) {
+            return None;
+        }
+
+        let rank = self.entries.partition_point(|entry| entry.score >= score);
+        self.entries.insert(
+            rank,
+            Entry {
+                name: name.into(),
+                score,
+            },
+        );
+        self.entries.truncate(TABLE_SIZE);
+
+        Some(rank)
+    }
+
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut scores = Self::default();
+
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let entry = line
+                .split_once(' ')
+                .and_then(|(score, name)| Some((score.parse::<u32>().ok()?, name.trim())));
+            let Some((score, name)) = entry else {
+                return Err(invalid_data(format!(
+                    "line {}: expected 'score name', found '{line}'",
+                    n + 1
+                )));
+            };
+
+            scores.insert(name, score);
+        }
+
+        Ok(scores)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Save the table, creating the parent directories as needed.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for HighScores {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# high scores")?;
+
+        for entry in &self.entries {
+            writeln!(f, "{} {}", entry.score, entry.name)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// File holding the high scores of `game`, in the user data directory of the
+/// platform. `None` when that directory cannot be found.
+pub fn default_path(game: &str) -> Option<PathBuf> {
+    Some(crate::user_dir("XDG_DATA_HOME", ".local/share")?.join(format!("{game}-highscores.txt")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(scores: &HighScores) -> Vec<&str> {
+        scores
+            .entries()
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn parses_entries_best_first() {
+        let scores =
+            HighScores::parse("# high scores\n\n800 BOB\n  12500 ALICE  \n800 MALLORY SMITH\n")
+                .unwrap();
+
+        assert_eq!(
+            scores.entries(),
+            [
+                Entry {
+                    name: "ALICE".to_string(),
+                    score: 12500,
+                },
+                Entry {
+                    name: "BOB".to_string(),
+                    score: 800,
+                },
+                Entry {
+                    name: "MALLORY SMITH".to_string(),
+                    score: 800,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for (text, line) in [
+            ("# high scores\n100 ALICE\nBOB\n", 3),
+            ("\n\n-5 BOB", 3),
+            ("100\n", 1),
+        ] {
+            let err = HighScores::parse(text).unwrap_err();
+
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(
+                err.to_string().starts_with(&format!("line {line}:")),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn ties_rank_below_existing_entries() {
+        let mut scores = HighScores::default();
+
+        assert_eq!(scores.insert("ALICE", 500), Some(0));
+        assert_eq!(scores.insert("BOB", 900), Some(0));
+        assert_eq!(scores.insert("CAROL", 500), Some(2));
+        assert_eq!(names(&scores), ["BOB", "ALICE", "CAROL"]);
+    }
+
+    #[test]
+    fn keeps_only_the_best_entries() {
+        let mut scores = HighScores::default();
+        for score in 1..=TABLE_SIZE as u32 {
+            assert_eq!(scores.insert(format!("P{score}"), score * 10), Some(0));
+        }
+
+        // Full table: the last score and below are out
+        assert!(!scores.qualifies(10));
+        assert_eq!(scores.insert("LOW", 10), None);
+        assert!(scores.qualifies(11));
+        assert_eq!(scores.insert("HIGH", 1000), Some(0));
+
+        assert_eq!(scores.entries().len(), TABLE_SIZE);
+        assert_eq!(scores.entries()[0].name, "HIGH");
+        assert_eq!(scores.entries()[TABLE_SIZE - 1].score, 20);
+    }
+
+    #[test]
+    fn zero_never_qualifies() {
+        let mut scores = HighScores::default();
+
+        assert!(!scores.qualifies(0));
+        assert_eq!(scores.insert("NOBODY", 0), None);
+        assert!(scores.entries().is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_text() {
+        let mut scores = HighScores::default();
+        scores.insert("ALICE", 12500);
+        scores.insert("BOB", 9800);
+        scores.insert("MALLORY SMITH", 9800);
+
+        let text = scores.to_string();
+        assert_eq!(
+            text,
+            "# high scores\n12500 ALICE\n9800 BOB\n9800 MALLORY SMITH\n"
+        );
+        assert_eq!(
+            HighScores::parse(&text).unwrap().entries(),
+            scores.entries()
+        );
+    }
+}
@@ -0,0 +1,618 @@
+//! Named actions bound to keys, gamepad buttons and axes.
+//!
+//! A game lists what the player can do with the [`Action`] trait and reads it
+//! through [`Actions`], sampled every frame from an [`InputSource`]: the raylib
+//! window, or a [`FakeInput`] set by hand when there is none.
+//!
+//! Every game loads its bindings from a text file, one action per line along
+//! with the raylib names of what it is bound to. Axes take the direction they
+//! are pushed to, and an action left with no bindings is disabled:
+//!
+//! ```text
+//! # asteroids bindings
+//! thrust = KEY_UP KEY_W GAMEPAD_AXIS_LEFT_Y-
+//! fire = KEY_SPACE GAMEPAD_BUTTON_RIGHT_FACE_DOWN
+//! hyperspace =
+//! ```
+//!
+//! Lines starting with '#' and empty lines are ignored, actions not in the file
+//! keep their default bindings. The file also holds the game settings listed in
+//! [`Action::SETTINGS`], as `setting = value` lines.
+
+use std::{
+    fmt, fs, io,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+use raylib::{
+    ffi::{GamepadAxis, GamepadButton, KeyboardKey},
+    prelude::*,
+};
+
+use crate::invalid_data;
+
+/// Stick deflection under which the stick is considered at rest.
+pub const AXIS_DEAD_ZONE: f32 = 0.15;
+
+/// Gamepad read by the bindings.
+const GAMEPAD: i32 = 0;
+
+/// Something the player does, whatever the device used for it.
+pub trait Action: Copy + PartialEq + 'static {
+    /// Every action of the game.
+    const ALL: &'static [Self];
+
+    /// Names of the other settings the bindings file may hold, read back with
+    /// [`Bindings::setting`].
+    const SETTINGS: &'static [&'static str] = &[];
+
+    /// Name of the action in bindings files.
+    fn name(self) -> &'static str;
+
+    /// What the action is bound to when the bindings file does not say.
+    fn default_bindings(self) -> &'static [Binding];
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Binding {
+    Key(KeyboardKey),
+    /// Button of the first gamepad.
+    Button(GamepadButton),
+    /// Axis of the first gamepad, pushed in the positive direction or not.
+    Axis {
+        axis: GamepadAxis,
+        positive: bool,
+    },
+}
+
+impl Binding {
+    /// Parse the raylib name of a key or gamepad button, or of an axis
+    /// followed by '+' or '-'.
+    pub fn parse(name: &str) -> Option<Self> {
+        if let Some(axis) = name.strip_suffix('+') {
+            return lookup(AXES, axis).map(|axis| Binding::Axis {
+                axis,
+                positive: true,
+            });
+        }
+
+        if let Some(axis) = name.strip_suffix('-') {
+            return lookup(AXES, axis).map(|axis| Binding::Axis {
+                axis,
+                positive: false,
+            });
+        }
+
+        lookup(KEYS, name)
+            .map(Binding::Key)
+            .or_else(|| lookup(BUTTONS, name).map(Binding::Button))
+    }
+
+    /// How far the binding is pushed, from 0 to 1. Keys and buttons are either
+    /// released or fully pushed, axes count from the end of the dead zone.
+    fn value(self, source: &impl InputSource) -> f32 {
+        match self {
+            Binding::Key(key) => source.is_key_down(key) as u8 as f32,
+            Binding::Button(button) => source.is_button_down(button) as u8 as f32,
+            Binding::Axis { axis, positive } => {
+                let movement = source.axis_movement(axis) * if positive { 1.0 } else { -1.0 };
+
+                ((movement - AXIS_DEAD_ZONE) / (1.0 - AXIS_DEAD_ZONE)).clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+/// The raylib name of the binding without its prefix, e.g. `SPACE` or
+/// `LEFT_Y-`, short enough for the game to tell the player what to press.
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, direction) = match *self {
+            Binding::Key(key) => (reverse_lookup(KEYS, key), ""),
+            Binding::Button(button) => (reverse_lookup(BUTTONS, button), ""),
+            Binding::Axis { axis, positive } => {
+                (reverse_lookup(AXES, axis), if positive { "+" } else { "-" })
+            }
+        };
+        let name = name.map_or("?", |name| {
+            ["KEY_", "GAMEPAD_BUTTON_", "GAMEPAD_AXIS_"]
+                .iter()
+                .find_map(|prefix| name.strip_prefix(prefix))
+                .unwrap_or(name)
+        });
+
+        write!(f, "{name}{direction}")
+    }
+}
+
+/// What every action of `A` is bound to.
+#[derive(Clone, Debug)]
+pub struct Bindings<A> {
+    bindings: Vec<Vec<Binding>>,
+    /// Values of the settings found in the file.
+    settings: Vec<(&'static str, String)>,
+    actions: PhantomData<A>,
+}
+
+impl<A: Action> Default for Bindings<A> {
+    fn default() -> Self {
+        let bindings = A::ALL
+            .iter()
+            .map(|action| action.default_bindings().to_vec())
+            .collect();
+        let settings = Vec::new();
+        let actions = PhantomData;
+
+        Self {
+            bindings,
+            settings,
+            actions,
+        }
+    }
+}
+
+impl<A: Action> Bindings<A> {
+    pub fn get(&self, action: A) -> &[Binding] {
+        &self.bindings[index(action)]
+    }
+
+    pub fn set(&mut self, action: A, bindings: Vec<Binding>) {
+        self.bindings[index(action)] = bindings;
+    }
+
+    /// The first binding of `action`, as shown in prompts, or "NOTHING" when
+    /// the action is disabled.
+    pub fn describe(&self, action: A) -> String {
+        self.get(action)
+            .first()
+            .map_or_else(|| "NOTHING".to_string(), Binding::to_string)
+    }
+
+    /// Value of the setting `name` in the file, `None` when not given.
+    pub fn setting(&self, name: &str) -> Option<&str> {
+        self.settings
+            .iter()
+            .find(|&&(setting, _)| setting == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut bindings = Self::default();
+
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((name, names)) = line.split_once('=') else {
+                return Err(invalid_data(format!(
+                    "line {}: expected 'action = bindings', found '{line}'",
+                    n + 1
+                )));
+            };
+
+            let name = name.trim();
+            if let Some(&setting) = A::SETTINGS.iter().find(|&&setting| setting == name) {
+                bindings.settings.retain(|&(set, _)| set != setting);
+                bindings.settings.push((setting, names.trim().to_string()));
+                continue;
+            }
+
+            let Some(&action) = A::ALL.iter().find(|action| action.name() == name) else {
+                return Err(invalid_data(format!(
+                    "line {}: unknown action '{name}'",
+                    n + 1
+                )));
+            };
+
+            let parsed = names
+                .split_whitespace()
+                .map(|name| {
+                    Binding::parse(name).ok_or_else(|| {
+                        invalid_data(format!("line {}: unknown binding '{name}'", n + 1))
+                    })
+                })
+                .collect::<io::Result<_>>()?;
+
+            bindings.set(action, parsed);
+        }
+
+        Ok(bindings)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Load the bindings of `game` from the user config directory. Problems
+    /// are reported on stderr and leave the game with the default bindings.
+    pub fn load_config(game: &str) -> Self {
+        let Some(path) = default_path(game) else {
+            return Self::default();
+        };
+
+        match Self::load(&path) {
+            Ok(bindings) => bindings,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(err) => {
+                eprintln!("{game}: cannot load bindings {}: {err}", path.display());
+                Self::default()
+            }
+        }
+    }
+}
+
+/// File holding the bindings of `game`, in the user config directory of the
+/// platform. `None` when that directory cannot be found.
+pub fn default_path(game: &str) -> Option<PathBuf> {
+    Some(crate::user_dir("XDG_CONFIG_HOME", ".config")?.join(format!("{game}-bindings.txt")))
+}
+
+/// State of the actions of `A`, sampled once per frame.
+pub struct Actions<A> {
+    bindings: Bindings<A>,
+    /// How far each action is pushed, from 0 to 1.
+    values: Vec<f32>,
+    /// Whether each action was down on the previous frame.
+    previous: Vec<bool>,
+}
+
+impl<A: Action> Actions<A> {
+    pub fn new(bindings: Bindings<A>) -> Self {
+        let values = vec![0.0; A::ALL.len()];
+        let previous = vec![false; A::ALL.len()];
+
+        Self {
+            bindings,
+            values,
+            previous,
+        }
+    }
+
+    pub fn bindings(&self) -> &Bindings<A> {
+        &self.bindings
+    }
+
+    /// Read the devices for a new frame. This should happen every frame, even
+    /// when the actions are not used, for presses to be told apart from held
+    /// actions.
+    pub fn update(&mut self, source: &impl InputSource) {
+        for (i, &action) in A::ALL.iter().enumerate() {
+            self.previous[i] = self.values[i] > 0.5;
+            self.values[i] = self
+                .bindings
+                .get(action)
+                .iter()
+                .map(|binding| binding.value(source))
+                .fold(0.0, f32::max);
+        }
+    }
+
+    /// How far `action` is pushed, from 0 to 1, by the strongest of its
+    /// bindings.
+    pub fn value(&self, action: A) -> f32 {
+        self.values[index(action)]
+    }
+
+    /// Whether `action` is held, axes being held once pushed halfway.
+    pub fn is_down(&self, action: A) -> bool {
+        self.value(action) > 0.5
+    }
+
+    /// Whether `action` started being held this frame.
+    pub fn is_pressed(&self, action: A) -> bool {
+        self.is_down(action) && !self.previous[index(action)]
+    }
+}
+
+/// Devices the actions are read from.
+pub trait InputSource {
+    fn is_key_down(&self, key: KeyboardKey) -> bool;
+
+    /// Whether `button` of the first gamepad is held.
+    fn is_button_down(&self, button: GamepadButton) -> bool;
+
+    /// Position of `axis` of the first gamepad, from -1 to 1.
+    fn axis_movement(&self, axis: GamepadAxis) -> f32;
+}
+
+impl InputSource for RaylibHandle {
+    fn is_key_down(&self, key: KeyboardKey) -> bool {
+        RaylibHandle::is_key_down(self, key)
+    }
+
+    fn is_button_down(&self, button: GamepadButton) -> bool {
+        self.is_gamepad_available(GAMEPAD) && self.is_gamepad_button_down(GAMEPAD, button)
+    }
+
+    fn axis_movement(&self, axis: GamepadAxis) -> f32 {
+        if self.is_gamepad_available(GAMEPAD) {
+            self.get_gamepad_axis_movement(GAMEPAD, axis)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Input source whose devices are set by hand, to drive a game without a
+/// window, e.g. in tests.
+#[derive(Clone, Debug, Default)]
+pub struct FakeInput {
+    keys: Vec<KeyboardKey>,
+    buttons: Vec<GamepadButton>,
+    axes: Vec<(GamepadAxis, f32)>,
+}
+
+impl FakeInput {
+    pub fn press_key(&mut self, key: KeyboardKey) {
+        if !self.keys.contains(&key) {
+            self.keys.push(key);
+        }
+    }
+
+    pub fn release_key(&mut self, key: KeyboardKey) {
+        self.keys.retain(|&held| held != key);
+    }
+
+    pub fn press_button(&mut self, button: GamepadButton) {
+        if !self.buttons.contains(&button) {
+            self.buttons.push(button);
+        }
+    }
+
+    pub fn release_button(&mut self, button: GamepadButton) {
+        self.buttons.retain(|&held| held != button);
+    }
+
+    /// Move `axis` to `movement`, from -1 to 1.
+    pub fn set_axis(&mut self, axis: GamepadAxis, movement: f32) {
+        self.axes.retain(|&(moved, _)| moved != axis);
+        self.axes.push((axis, movement));
+    }
+
+    /// Release every key and button, and center the axes.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl InputSource for FakeInput {
+    fn is_key_down(&self, key: KeyboardKey) -> bool {
+        self.keys.contains(&key)
+    }
+
+    fn is_button_down(&self, button: GamepadButton) -> bool {
+        self.buttons.contains(&button)
+    }
+
+    fn axis_movement(&self, axis: GamepadAxis) -> f32 {
+        self.axes
+            .iter()
+            .find(|&&(moved, _)| moved == axis)
+            .map_or(0.0, |&(_, movement)| movement)
+    }
+}
+
+fn index<A: Action>(action: A) -> usize {
+    A::ALL
+        .iter()
+        .position(|&listed| listed == action)
+        .expect("action missing from Action::ALL")
+}
+
+fn lookup<T: Copy>(names: &[(&str, T)], name: &str) -> Option<T> {
+    names
+        .iter()
+        .find(|&&(listed, _)| listed == name)
+        .map(|&(_, value)| value)
+}
+
+fn reverse_lookup<T: Copy + PartialEq>(
+    names: &[(&'static str, T)],
+    value: T,
+) -> Option<&'static str> {
+    names
+        .iter()
+        .find(|&&(_, listed)| listed == value)
+        .map(|&(name, _)| name)
+}
+
+/// Table of the raylib names of some enum variants.
+macro_rules! names {
+    ($ty:ident: $($variant:ident),* $(,)?) => {
+        &[$((stringify!($variant), $ty::$variant)),*]
+    };
+}
+
+const KEYS: &[(&str, KeyboardKey)] = names!(KeyboardKey:
+    KEY_APOSTROPHE, KEY_COMMA, KEY_MINUS, KEY_PERIOD, KEY_SLASH, KEY_SEMICOLON, KEY_EQUAL,
+    KEY_LEFT_BRACKET, KEY_BACKSLASH, KEY_RIGHT_BRACKET, KEY_GRAVE,
+    KEY_ZERO, KEY_ONE, KEY_TWO, KEY_THREE, KEY_FOUR, KEY_FIVE, KEY_SIX, KEY_SEVEN, KEY_EIGHT,
+    KEY_NINE,
+    KEY_A, KEY_B, KEY_C, KEY_D, KEY_E, KEY_F, KEY_G, KEY_H, KEY_I, KEY_J, KEY_K, KEY_L, KEY_M,
+    KEY_N, KEY_O, KEY_P, KEY_Q, KEY_R, KEY_S, KEY_T, KEY_U, KEY_V, KEY_W, KEY_X, KEY_Y, KEY_Z,
+    KEY_SPACE, KEY_ESCAPE, KEY_ENTER, KEY_TAB, KEY_BACKSPACE, KEY_INSERT, KEY_DELETE,
+    KEY_RIGHT, KEY_LEFT, KEY_DOWN, KEY_UP, KEY_PAGE_UP, KEY_PAGE_DOWN, KEY_HOME, KEY_END,
+    KEY_F1, KEY_F2, KEY_F3, KEY_F4, KEY_F5, KEY_F6, KEY_F7, KEY_F8, KEY_F9, KEY_F10, KEY_F11,
+    KEY_F12,
+    KEY_LEFT_SHIFT, KEY_LEFT_CONTROL, KEY_LEFT_ALT, KEY_RIGHT_SHIFT, KEY_RIGHT_CONTROL,
+    KEY_RIGHT_ALT,
+    KEY_KP_0, KEY_KP_1, KEY_KP_2, KEY_KP_3, KEY_KP_4, KEY_KP_5, KEY_KP_6, KEY_KP_7, KEY_KP_8,
+    KEY_KP_9, KEY_KP_DECIMAL, KEY_KP_DIVIDE, KEY_KP_MULTIPLY, KEY_KP_SUBTRACT, KEY_KP_ADD,
+    KEY_KP_ENTER,
+);
+
+const BUTTONS: &[(&str, GamepadButton)] = names!(GamepadButton:
+    GAMEPAD_BUTTON_LEFT_FACE_UP, GAMEPAD_BUTTON_LEFT_FACE_RIGHT, GAMEPAD_BUTTON_LEFT_FACE_DOWN,
+    GAMEPAD_BUTTON_LEFT_FACE_LEFT, GAMEPAD_BUTTON_RIGHT_FACE_UP, GAMEPAD_BUTTON_RIGHT_FACE_RIGHT,
+    GAMEPAD_BUTTON_RIGHT_FACE_DOWN, GAMEPAD_BUTTON_RIGHT_FACE_LEFT,
+    GAMEPAD_BUTTON_LEFT_TRIGGER_1, GAMEPAD_BUTTON_LEFT_TRIGGER_2, GAMEPAD_BUTTON_RIGHT_TRIGGER_1,
+    GAMEPAD_BUTTON_RIGHT_TRIGGER_2, GAMEPAD_BUTTON_MIDDLE_LEFT, GAMEPAD_BUTTON_MIDDLE,
+    GAMEPAD_BUTTON_MIDDLE_RIGHT, GAMEPAD_BUTTON_LEFT_THUMB, GAMEPAD_BUTTON_RIGHT_THUMB,
+);
+
+const AXES: &[(&str, GamepadAxis)] = names!(GamepadAxis:
+    GAMEPAD_AXIS_LEFT_X, GAMEPAD_AXIS_LEFT_Y, GAMEPAD_AXIS_RIGHT_X, GAMEPAD_AXIS_RIGHT_Y,
+    GAMEPAD_AXIS_LEFT_TRIGGER, GAMEPAD_AXIS_RIGHT_TRIGGER,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Test {
+        Fire,
+        Left,
+    }
+
+    impl Action for Test {
+        const ALL: &'static [Self] = &[Test::Fire, Test::Left];
+        const SETTINGS: &'static [&'static str] = &["mode"];
+
+        fn name(self) -> &'static str {
+            match self {
+                Test::Fire => "fire",
+                Test::Left => "left",
+            }
+        }
+
+        fn default_bindings(self) -> &'static [Binding] {
+            match self {
+                Test::Fire => &[Binding::Key(KeyboardKey::KEY_SPACE)],
+                Test::Left => &[Binding::Axis {
+                    axis: GamepadAxis::GAMEPAD_AXIS_LEFT_X,
+                    positive: false,
+                }],
+            }
+        }
+    }
+
+    fn error(text: &str) -> String {
+        Bindings::<Test>::parse(text).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parses_bindings() {
+        let bindings = Bindings::<Test>::parse(
+            "# comment\n\nfire = KEY_Z GAMEPAD_BUTTON_RIGHT_FACE_DOWN GAMEPAD_AXIS_RIGHT_TRIGGER+\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            bindings.get(Test::Fire),
+            [
+                Binding::Key(KeyboardKey::KEY_Z),
+                Binding::Button(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN),
+                Binding::Axis {
+                    axis: GamepadAxis::GAMEPAD_AXIS_RIGHT_TRIGGER,
+                    positive: true,
+                },
+            ]
+        );
+        // Not in the file, so left to the defaults
+        assert_eq!(bindings.get(Test::Left), Test::Left.default_bindings());
+    }
+
+    #[test]
+    fn empty_bindings_disable_the_action() {
+        let bindings = Bindings::<Test>::parse("left =\n").unwrap();
+
+        assert!(bindings.get(Test::Left).is_empty());
+        assert_eq!(bindings.describe(Test::Left), "NOTHING");
+    }
+
+    #[test]
+    fn reads_settings_along_with_bindings() {
+        let bindings =
+            Bindings::<Test>::parse("mode = fast\nfire = KEY_Z\nmode =  slow  \n").unwrap();
+
+        assert_eq!(bindings.setting("mode"), Some("slow"));
+        assert_eq!(bindings.setting("other"), None);
+        assert_eq!(bindings.get(Test::Fire), [Binding::Key(KeyboardKey::KEY_Z)]);
+        assert!(error("other = 1").contains("unknown action 'other'"));
+    }
+
+    #[test]
+    fn rejects_bad_lines() {
+        assert!(error("jump = KEY_A").contains("line 1: unknown action 'jump'"));
+        assert!(error("\nfire = KEY_NOPE").contains("line 2: unknown binding 'KEY_NOPE'"));
+        assert!(error("fire = GAMEPAD_AXIS_LEFT_X").contains("unknown binding"));
+        assert!(error("fire KEY_A").contains("line 1: expected 'action = bindings'"));
+    }
+
+    #[test]
+    fn describes_bindings_without_prefix() {
+        let bindings = Bindings::<Test>::parse(
+            "fire = GAMEPAD_BUTTON_RIGHT_FACE_DOWN KEY_Z\nleft = GAMEPAD_AXIS_LEFT_X-",
+        )
+        .unwrap();
+
+        assert_eq!(Binding::Key(KeyboardKey::KEY_SPACE).to_string(), "SPACE");
+        assert_eq!(bindings.describe(Test::Fire), "RIGHT_FACE_DOWN");
+        assert_eq!(bindings.describe(Test::Left), "LEFT_X-");
+    }
+
+    #[test]
+    fn presses_only_last_one_frame() {
+        let mut actions = Actions::new(Bindings::<Test>::default());
+        let mut input = FakeInput::default();
+
+        actions.update(&input);
+        assert!(!actions.is_down(Test::Fire));
+        assert!(!actions.is_pressed(Test::Fire));
+
+        input.press_key(KeyboardKey::KEY_SPACE);
+        actions.update(&input);
+        assert!(actions.is_down(Test::Fire));
+        assert!(actions.is_pressed(Test::Fire));
+
+        // Still held, but no longer a new press
+        actions.update(&input);
+        assert!(actions.is_down(Test::Fire));
+        assert!(!actions.is_pressed(Test::Fire));
+
+        input.release_key(KeyboardKey::KEY_SPACE);
+        actions.update(&input);
+        assert!(!actions.is_down(Test::Fire));
+
+        input.press_key(KeyboardKey::KEY_SPACE);
+        actions.update(&input);
+        assert!(actions.is_pressed(Test::Fire));
+    }
+
+    #[test]
+    fn axes_count_from_the_dead_zone() {
+        let mut actions = Actions::new(Bindings::<Test>::default());
+        let mut input = FakeInput::default();
+
+        input.set_axis(GamepadAxis::GAMEPAD_AXIS_LEFT_X, -AXIS_DEAD_ZONE / 2.0);
+        actions.update(&input);
+        assert_eq!(actions.value(Test::Left), 0.0);
+
+        // Barely out of the dead zone: pushed a bit, but not held
+        input.set_axis(GamepadAxis::GAMEPAD_AXIS_LEFT_X, -0.3);
+        actions.update(&input);
+        let value = actions.value(Test::Left);
+        assert!(value > 0.1 && value < 0.2, "{value}");
+        assert!(!actions.is_down(Test::Left));
+
+        input.set_axis(GamepadAxis::GAMEPAD_AXIS_LEFT_X, -1.0);
+        actions.update(&input);
+        assert_eq!(actions.value(Test::Left), 1.0);
+        assert!(actions.is_pressed(Test::Left));
+
+        // The other way is another binding
+        input.set_axis(GamepadAxis::GAMEPAD_AXIS_LEFT_X, 1.0);
+        actions.update(&input);
+        assert_eq!(actions.value(Test::Left), 0.0);
+
+        input.set_axis(GamepadAxis::GAMEPAD_AXIS_LEFT_X, -1.0);
+        input.reset();
+        actions.update(&input);
+        assert!(!actions.is_down(Test::Left));
+    }
+}
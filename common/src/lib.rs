@@ -0,0 +1,32 @@
+//! Code shared by the games of this repository.
+
+use std::{env, io, path::PathBuf};
+
+pub mod highscores;
+pub mod input;
+pub mod scoreboard;
+
+/// Directory where the games keep the files of the user. On Unix it lives in
+/// the XDG directory of `xdg_var`, `fallback` in the home directory when it is
+/// not set. `None` when it cannot be found.
+fn user_dir(xdg_var: &str, fallback: &str) -> Option<PathBuf> {
+    let absolute = |var| {
+        env::var_os(var)
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+    };
+
+    let dir = if cfg!(windows) {
+        absolute("APPDATA")?
+    } else if cfg!(target_os = "macos") {
+        absolute("HOME")?.join("Library/Application Support")
+    } else {
+        absolute(xdg_var).or_else(|| Some(absolute("HOME")?.join(fallback)))?
+    };
+
+    Some(dir.join("raylib-rs-games"))
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
@@ -0,0 +1,179 @@
+//! The high-score table of a game as shown on its game over screen, along with
+//! the name prompt for new entries.
+
+use std::{io, path::PathBuf};
+
+use raylib::{core::text::measure_text, ffi::KeyboardKey, prelude::*};
+
+use crate::highscores::{self, HighScores, NAME_LENGTH};
+
+/// Name used when the player confirms an empty one.
+const DEFAULT_NAME: &str = "PLAYER";
+
+pub struct ScoreBoard {
+    game: &'static str,
+    scores: HighScores,
+    /// Where the table is saved, `None` when it should not be, e.g. because
+    /// the existing file could not be read.
+    path: Option<PathBuf>,
+    /// Name being typed, along with the score it is for.
+    entry: Option<(String, u32)>,
+    /// Rank of the last entry added, highlighted in the table.
+    last_rank: Option<usize>,
+}
+
+impl ScoreBoard {
+    /// Load the table of `game` from the user data directory. Problems are
+    /// reported on stderr and leave the game with an empty table.
+    pub fn load(game: &'static str) -> Self {
+        let mut path = highscores::default_path(game);
+
+        let scores = match path.as_ref().map(HighScores::load) {
+            Some(Ok(scores)) => scores,
+            Some(Err(err)) if err.kind() == io::ErrorKind::NotFound => HighScores::default(),
+            Some(Err(err)) => {
+                eprintln!(
+                    "{game}: cannot load high scores {}: {err}",
+                    path.as_ref().unwrap().display()
+                );
+                path = None;
+                HighScores::default()
+            }
+            None => {
+                eprintln!("{game}: no data directory, high scores will not be saved");
+                HighScores::default()
+            }
+        };
+
+        Self {
+            game,
+            scores,
+            path,
+            entry: None,
+            last_rank: None,
+        }
+    }
+
+    /// Ask for the player's name if `score` makes it in the table.
+    pub fn submit(&mut self, score: u32) {
+        self.last_rank = None;
+
+        if self.scores.qualifies(score) {
+            self.entry = Some((String::new(), score));
+        }
+    }
+
+    pub fn is_entering_name(&self) -> bool {
+        self.entry.is_some()
+    }
+
+    /// Read the name typed on the keyboard, the entry is added and saved once
+    /// `confirm` is set, e.g. by the action the game restarts with, or [ENTER]
+    /// is pressed.
+    pub fn update(&mut self, rl: &RaylibHandle, confirm: bool) {
+        let Some((name, score)) = &mut self.entry else {
+            return;
+        };
+
+        while let Some(c) = rl.get_char_pressed() {
+            if name.len() < NAME_LENGTH && (c.is_ascii_graphic() || c == ' ' && !name.is_empty()) {
+                name.push(c.to_ascii_uppercase());
+            }
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+            name.pop();
+        }
+
+        if confirm || rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            let name = match name.trim() {
+                "" => DEFAULT_NAME,
+                name => name,
+            };
+
+            self.last_rank = self.scores.insert(name, *score);
+            self.entry = None;
+            self.save();
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        if let Err(err) = self.scores.save(path) {
+            eprintln!(
+                "{}: cannot save high scores {}: {err}",
+                self.game,
+                path.display()
+            );
+        }
+    }
+
+    /// Draw the name prompt over the middle of the screen, or the table below it.
+    /// The prompt asks to press `confirm`, the name of what confirms the entry.
+    pub fn draw(&self, d: &RaylibDrawHandle, (width, height): (i32, i32), confirm: &str) {
+        let (half_width, half_height) = (width / 2, height / 2);
+
+        if let Some((name, score)) = &self.entry {
+            d.draw_rectangle(
+                half_width - 200,
+                half_height - 90,
+                400,
+                120,
+                Color::RAYWHITE,
+            );
+            d.draw_rectangle_lines(half_width - 200, half_height - 90, 400, 120, Color::GRAY);
+
+            let lines = [
+                (format!("NEW HIGH SCORE: {score}"), 20, Color::MAROON),
+                (format!("NAME: {name}_"), 20, Color::DARKGRAY),
+                (
+                    format!("TYPE YOUR NAME AND PRESS [{confirm}]"),
+                    10,
+                    Color::GRAY,
+                ),
+            ];
+            for (i, (text, size, color)) in lines.iter().enumerate() {
+                d.draw_text(
+                    text,
+                    half_width - measure_text(text, *size) / 2,
+                    half_height - 75 + 35 * i as i32,
+                    *size,
+                    *color,
+                );
+            }
+
+            return;
+        }
+
+        d.draw_text(
+            "HIGH SCORES",
+            half_width - measure_text("HIGH SCORES", 20) / 2,
+            half_height,
+            20,
+            Color::GRAY,
+        );
+
+        for (i, entry) in self.scores.entries().iter().enumerate() {
+            let y = half_height + 25 + 20 * i as i32;
+            let color = if self.last_rank == Some(i) {
+                Color::MAROON
+            } else {
+                Color::LIGHTGRAY
+            };
+            let score = entry.score.to_string();
+
+            d.draw_text(&format!("{:>2}.", i + 1), half_width - 150, y, 20, color);
+            d.draw_text(&entry.name, half_width - 105, y, 20, color);
+            d.draw_text(
+                &score,
+                half_width + 150 - measure_text(&score, 20),
+                y,
+                20,
+                color,
+            );
+        }
+    }
+}